@@ -0,0 +1,121 @@
+use crate::{context::Environment, Settings};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Condvar, Mutex},
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+/// tells a running test it should stop
+///
+/// The token is cancelled when the test timed out. The runner then gives
+/// the test a [`GracePeriod`] to return before reporting it as leaked: long
+/// running tests should poll the token (or wait on it instead of sleeping)
+/// and return as soon as possible.
+///
+/// [`GracePeriod`]: crate::settings::GracePeriod
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<(Mutex<State>, Condvar)>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    cancelled: bool,
+    /// the async tasks waiting for the cancellation
+    wakers: Vec<Waker>,
+}
+
+/// future completing once the test is cancelled, see
+/// [`CancellationToken::cancelled`]
+#[derive(Debug)]
+pub struct Cancelled {
+    token: CancellationToken,
+}
+
+impl CancellationToken {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// the token of the test running with the given settings
+    ///
+    /// This is for the types implementing [`IsTest`] directly, the tests
+    /// taking a [`TestContext`] can use [`TestContext::cancellation`]. The
+    /// returned token is never cancelled if the settings are not the ones
+    /// of a test run by an [`Executor`].
+    ///
+    /// [`IsTest`]: crate::IsTest
+    /// [`TestContext`]: crate::TestContext
+    /// [`TestContext::cancellation`]: crate::TestContext::cancellation
+    /// [`Executor`]: crate::Executor
+    pub fn of(settings: &Settings) -> Self {
+        settings
+            .get::<Environment>()
+            .map(|environment| environment.cancellation.clone())
+            .unwrap_or_default()
+    }
+
+    /// returns `true` if the test has been cancelled
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.0.lock().unwrap().cancelled
+    }
+
+    /// block until the test is cancelled
+    pub fn wait(&self) {
+        let (lock, cvar) = &*self.inner;
+        let _state = cvar
+            .wait_while(lock.lock().unwrap(), |state| !state.cancelled)
+            .unwrap();
+    }
+
+    /// complete once the test is cancelled, the async version of
+    /// [`CancellationToken::wait`]
+    pub fn cancelled(&self) -> Cancelled {
+        Cancelled {
+            token: self.clone(),
+        }
+    }
+
+    /// block until the test is cancelled or the `duration` elapsed
+    ///
+    /// returns `true` if the test has been cancelled, this can be used
+    /// in place of [`std::thread::sleep`] in long running tests.
+    pub fn wait_timeout(&self, duration: Duration) -> bool {
+        let (lock, cvar) = &*self.inner;
+        let (state, _) = cvar
+            .wait_timeout_while(lock.lock().unwrap(), duration, |state| !state.cancelled)
+            .unwrap();
+        state.cancelled
+    }
+
+    pub(crate) fn cancel(&self) {
+        let (lock, cvar) = &*self.inner;
+        let wakers = {
+            let mut state = lock.lock().unwrap();
+            state.cancelled = true;
+            std::mem::take(&mut state.wakers)
+        };
+        cvar.notify_all();
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+impl Future for Cancelled {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.token.inner.0.lock().unwrap();
+        if state.cancelled {
+            return Poll::Ready(());
+        }
+        if !state.wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+            state.wakers.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
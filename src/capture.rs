@@ -0,0 +1,234 @@
+use std::{
+    io::{self, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, MutexGuard, PoisonError,
+    },
+};
+
+/// set in the child processes of [`Isolation::Process`], their parent
+/// captures their output already
+///
+/// [`Isolation::Process`]: crate::settings::Isolation::Process
+static DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// held by the test capturing its output, whatever runs it: the output of
+/// the whole process is redirected so only one test captures it at a time
+static CAPTURING: Mutex<()> = Mutex::new(());
+
+/// the redirection of the standard output and error of the process, while
+/// a test is capturing its output
+static REDIRECTION: Mutex<Option<imp::Redirection>> = Mutex::new(None);
+
+/// the output of a test being captured
+pub(crate) struct Guard {
+    _capturing: MutexGuard<'static, ()>,
+}
+
+/// the standard output of the process, even while the output of the
+/// tests is captured
+///
+/// This is where [`Progress::stdout`] writes so the captured output of
+/// the running tests does not include the progress of the run.
+///
+/// [`Progress::stdout`]: crate::Progress::stdout
+#[derive(Debug, Default)]
+pub struct Terminal {
+    _private: (),
+}
+
+/// returns `true` if the output of the tests can be captured
+///
+/// The output is captured by redirecting the standard output and error of
+/// the whole process. This is never done from the test harness (it runs
+/// every test function in a thread named after it): it captures the output
+/// of its tests already, or was asked not to with `--nocapture`, and its
+/// own output would end up in the captured text.
+pub(crate) fn available() -> bool {
+    if !cfg!(unix) || DISABLED.load(Ordering::Relaxed) {
+        return false;
+    }
+
+    let libtest = matches!(std::thread::current().name(), Some(name) if name != "main");
+    !libtest
+}
+
+pub(crate) fn disable() {
+    DISABLED.store(true, Ordering::Relaxed);
+}
+
+/// start capturing the output of a test
+///
+/// The whole process is redirected: this waits for the test capturing its
+/// output already, if any, so the tests do not capture the output of each
+/// other even when they are run by different executors. The executor does
+/// not start the other tests of its run while a test is capturing either.
+pub(crate) fn start() -> Option<Guard> {
+    // a test panicking while capturing does not leave the capture broken
+    let capturing = CAPTURING.lock().unwrap_or_else(PoisonError::into_inner);
+    let _ = io::stdout().flush();
+    let mut redirection = REDIRECTION.lock().unwrap();
+    *redirection = Some(imp::Redirection::new().ok()?);
+    Some(Guard {
+        _capturing: capturing,
+    })
+}
+
+impl Guard {
+    /// stop capturing and return everything written since the start
+    pub(crate) fn finish(self) -> String {
+        let _ = io::stdout().flush();
+        let redirection = REDIRECTION
+            .lock()
+            .unwrap()
+            .take()
+            .expect("the redirection lasts as long as a test is capturing");
+        let output = redirection.read();
+        // restore the standard output and error before another test
+        // starts capturing
+        drop(redirection);
+        String::from_utf8_lossy(&output).into_owned()
+    }
+}
+
+impl Write for Terminal {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match REDIRECTION.lock().unwrap().as_ref() {
+            Some(redirection) => redirection.write(buf),
+            None => io::stdout().write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stdout().flush()
+    }
+}
+
+#[cfg(unix)]
+mod imp {
+    use std::{
+        env,
+        fs::{self, File, OpenOptions},
+        io::{self, Write},
+        mem::ManuallyDrop,
+        os::unix::{
+            fs::FileExt,
+            io::{AsRawFd, FromRawFd, RawFd},
+        },
+        process,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    const STDOUT: RawFd = 1;
+    const STDERR: RawFd = 2;
+
+    pub(super) struct Redirection {
+        /// where the standard output and error are redirected to
+        file: File,
+        /// the original standard output and error
+        stdout: RawFd,
+        stderr: RawFd,
+    }
+
+    impl Redirection {
+        pub(super) fn new() -> io::Result<Self> {
+            static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+            let path = env::temp_dir().join(format!(
+                "scaffolding-capture-{}-{}",
+                process::id(),
+                COUNT.fetch_add(1, Ordering::Relaxed)
+            ));
+            let file = OpenOptions::new()
+                .read(true)
+                .append(true)
+                .create_new(true)
+                .open(&path)?;
+            fs::remove_file(&path)?;
+
+            let stdout = duplicate(STDOUT)?;
+            let stderr = match duplicate(STDERR) {
+                Ok(stderr) => stderr,
+                Err(error) => {
+                    close(stdout);
+                    return Err(error);
+                }
+            };
+            let redirection = Self {
+                file,
+                stdout,
+                stderr,
+            };
+            redirect(redirection.file.as_raw_fd(), STDOUT)?;
+            redirect(redirection.file.as_raw_fd(), STDERR)?;
+
+            Ok(redirection)
+        }
+
+        /// everything written since the redirection started
+        pub(super) fn read(&self) -> Vec<u8> {
+            let len = self.file.metadata().map_or(0, |metadata| metadata.len());
+            let mut output = vec![0; len as usize];
+            let read = self.file.read_at(&mut output, 0).unwrap_or_default();
+            output.truncate(read);
+            output
+        }
+
+        pub(super) fn write(&self, buf: &[u8]) -> io::Result<usize> {
+            // the file descriptor is still owned by the redirection
+            let mut stdout = ManuallyDrop::new(unsafe { File::from_raw_fd(self.stdout) });
+            stdout.write(buf)
+        }
+    }
+
+    impl Drop for Redirection {
+        fn drop(&mut self) {
+            let _ = redirect(self.stdout, STDOUT);
+            let _ = redirect(self.stderr, STDERR);
+            close(self.stdout);
+            close(self.stderr);
+        }
+    }
+
+    fn duplicate(fd: RawFd) -> io::Result<RawFd> {
+        match unsafe { libc::dup(fd) } {
+            -1 => Err(io::Error::last_os_error()),
+            fd => Ok(fd),
+        }
+    }
+
+    fn redirect(from: RawFd, to: RawFd) -> io::Result<()> {
+        match unsafe { libc::dup2(from, to) } {
+            -1 => Err(io::Error::last_os_error()),
+            _ => Ok(()),
+        }
+    }
+
+    fn close(fd: RawFd) {
+        unsafe { libc::close(fd) };
+    }
+}
+
+#[cfg(not(unix))]
+mod imp {
+    use std::io;
+
+    pub(super) struct Redirection;
+
+    impl Redirection {
+        pub(super) fn new() -> io::Result<Self> {
+            Err(io::Error::new(
+                io::ErrorKind::Other,
+                "capturing the output is not supported on this platform",
+            ))
+        }
+
+        pub(super) fn read(&self) -> Vec<u8> {
+            Vec::new()
+        }
+
+        pub(super) fn write(&self, _: &[u8]) -> io::Result<usize> {
+            unreachable!()
+        }
+    }
+}
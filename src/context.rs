@@ -0,0 +1,175 @@
+use crate::{
+    fixture::Fixtures,
+    rng::{self, Rng},
+    settings::Setting,
+    CancellationToken, Settings,
+};
+use std::{
+    any::Any,
+    fmt::{self, Write as _},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+/// the context of a running test
+///
+/// Tests taking a `&TestContext` argument can access the [`Settings`]
+/// computed for them, their path in the tree, a seeded random generator,
+/// a logger whose lines are added to the details of the test's result
+/// and the fixtures provided by the setups of their groups:
+///
+/// ```
+/// # use scaffolding::{group, setup, single, TestContext};
+/// struct Config {
+///     url: String,
+/// }
+///
+/// let tests = group!(
+///     "with a config",
+///     [
+///         setup!("load config", |context: &TestContext| {
+///             context.provide(Config {
+///                 url: "http://localhost".to_owned(),
+///             })
+///         }),
+///         single!("uses the config", |context: &TestContext| {
+///             let config = context.fixture::<Config>().unwrap();
+///             context.log(format!("connecting to {}", config.url));
+///             config.url.starts_with("http")
+///         }),
+///     ]
+/// );
+/// # scaffolding::scaffold(tests);
+/// ```
+pub struct TestContext {
+    settings: Settings,
+    environment: Arc<Environment>,
+    fixtures: Arc<Fixtures>,
+    logs: Arc<Mutex<String>>,
+    seeded: Arc<AtomicBool>,
+}
+
+/// what the executor knows about the test it is running
+#[derive(Debug)]
+pub(crate) struct Environment {
+    pub(crate) path: String,
+    /// the seed of the run, see [`rng::SEED_ENV`]
+    pub(crate) seed: u64,
+    pub(crate) cancellation: CancellationToken,
+}
+
+impl Setting for Environment {}
+
+impl Environment {
+    /// the environment of a test running outside of an executor
+    fn detached() -> Self {
+        Self {
+            path: String::new(),
+            seed: rng::random_seed(),
+            cancellation: CancellationToken::new(),
+        }
+    }
+}
+
+impl TestContext {
+    pub(crate) fn new(settings: &Settings) -> Self {
+        Self {
+            settings: settings.clone(),
+            environment: settings
+                .get::<Environment>()
+                .unwrap_or_else(|| Arc::new(Environment::detached())),
+            fixtures: settings.get::<Fixtures>().unwrap_or_else(Fixtures::root),
+            logs: Arc::new(Mutex::new(String::new())),
+            seeded: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// the settings in effect for this test
+    #[inline]
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// the full path of the test: the names of its groups and its
+    /// own name separated with `::`
+    #[inline]
+    pub fn path(&self) -> &str {
+        &self.environment.path
+    }
+
+    /// the token telling the test it has been cancelled
+    #[inline]
+    pub fn cancellation(&self) -> &CancellationToken {
+        &self.environment.cancellation
+    }
+
+    /// shortcut for `context.cancellation().is_cancelled()`
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation().is_cancelled()
+    }
+
+    /// a random generator seeded for this test
+    ///
+    /// The seed derives from the seed of the run and the path of the
+    /// test: set the `SCAFFOLDING_SEED` environment variable to the seed
+    /// reported with the failure to reproduce it. Every call returns a
+    /// generator starting from the same seed.
+    pub fn rng(&self) -> Rng {
+        self.seeded.store(true, Ordering::Relaxed);
+        Rng::new(rng::derive(self.environment.seed, self.path()))
+    }
+
+    /// add a line to the details of the test's result
+    pub fn log<D>(&self, line: D)
+    where
+        D: fmt::Display,
+    {
+        let mut logs = self.logs.lock().unwrap();
+        let _ = writeln!(logs, "{}", line);
+    }
+
+    /// get the fixture of the given type provided by the setup of
+    /// this test's group or of one of the enclosing groups
+    pub fn fixture<T>(&self) -> Option<Arc<T>>
+    where
+        T: Any + Send + Sync,
+    {
+        self.fixtures.get()
+    }
+
+    /// provide a fixture to all the tests of the group
+    ///
+    /// This is meant to be called from a setup, the fixtures are
+    /// dropped once all the tests and teardowns of the group completed.
+    /// A value of the same type replaces the previous one.
+    pub fn provide<T>(&self, value: T)
+    where
+        T: Any + Send + Sync,
+    {
+        self.fixtures.insert(value)
+    }
+
+    /// what the test left behind to be added to its result's details
+    ///
+    /// this can be called after the test has been moved into its thread.
+    pub(crate) fn report(&self) -> impl FnOnce(bool) -> String {
+        let logs = Arc::clone(&self.logs);
+        let seeded = Arc::clone(&self.seeded);
+        let seed = self.environment.seed;
+        move |failed| {
+            let mut report = std::mem::take(&mut *logs.lock().unwrap());
+            if failed && seeded.load(Ordering::Relaxed) {
+                let _ = writeln!(
+                    report,
+                    "random generator seeded from the run seed {seed}, set {env}={seed} to reproduce",
+                    seed = seed,
+                    env = rng::SEED_ENV,
+                );
+            }
+            report
+        }
+    }
+}
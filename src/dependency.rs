@@ -0,0 +1,143 @@
+use crate::{test_tree::PATH_SEPARATOR, TestName};
+use std::{error::Error, fmt};
+
+/// error in the dependencies declared between the tests
+///
+/// these errors are found before any of the test is executed.
+#[derive(Debug)]
+pub enum DependencyError {
+    /// the dependency does not match any test or group
+    Unknown { test: String, dependency: TestName },
+    /// the dependency matches more than one test or group
+    Ambiguous {
+        test: String,
+        dependency: TestName,
+        candidates: Vec<String>,
+    },
+    /// the tests depends on each other, the last one depending on the first one
+    Cycle { tests: Vec<String> },
+}
+
+/// a test or a group that can be depended on
+pub(crate) struct Target {
+    pub(crate) path: String,
+    /// the jobs of the test or of the group
+    pub(crate) jobs: Vec<usize>,
+}
+
+/// find the target matching the given dependency
+///
+/// the dependency needs to match the end of the target's path, if more than
+/// one target match, the one with the exact path is selected.
+pub(crate) fn resolve<'a>(
+    targets: &'a [Target],
+    test: &str,
+    dependency: &TestName,
+) -> Result<&'a Target, DependencyError> {
+    let suffix = format!("{}{}", PATH_SEPARATOR, dependency);
+    let candidates: Vec<&Target> = targets
+        .iter()
+        .filter(|target| target.path == *dependency || target.path.ends_with(&suffix))
+        .collect();
+
+    match candidates.as_slice() {
+        [] => Err(DependencyError::Unknown {
+            test: test.to_owned(),
+            dependency: dependency.clone(),
+        }),
+        [target] => Ok(target),
+        candidates => candidates
+            .iter()
+            .find(|target| target.path == *dependency)
+            .copied()
+            .ok_or_else(|| DependencyError::Ambiguous {
+                test: test.to_owned(),
+                dependency: dependency.clone(),
+                candidates: candidates
+                    .iter()
+                    .map(|target| target.path.clone())
+                    .collect(),
+            }),
+    }
+}
+
+/// look for a cycle in the dependency graph
+///
+/// `edges` returns the direct dependencies of the given node, the
+/// returned cycle is the list of nodes part of the cycle.
+pub(crate) fn find_cycle<'a, F, E>(nodes: usize, edges: F) -> Option<Vec<usize>>
+where
+    F: Fn(usize) -> E,
+    E: Iterator<Item = &'a usize>,
+{
+    #[derive(Clone, Copy, PartialEq)]
+    enum Mark {
+        New,
+        Visiting,
+        Visited,
+    }
+
+    let mut marks = vec![Mark::New; nodes];
+    for root in 0..nodes {
+        if marks[root] != Mark::New {
+            continue;
+        }
+
+        // depth first search, keeping the path from the root to the
+        // current node so the cycle can be returned
+        let mut path = vec![(root, edges(root))];
+        marks[root] = Mark::Visiting;
+        while let Some((node, next)) = path.last_mut() {
+            let node = *node;
+            match next.next().copied() {
+                Some(next) if marks[next] == Mark::Visiting => {
+                    let start = path.iter().position(|(node, _)| *node == next).unwrap();
+                    return Some(path[start..].iter().map(|(node, _)| *node).collect());
+                }
+                Some(next) if marks[next] == Mark::New => {
+                    marks[next] = Mark::Visiting;
+                    path.push((next, edges(next)));
+                }
+                Some(_) => (),
+                None => {
+                    marks[node] = Mark::Visited;
+                    path.pop();
+                }
+            }
+        }
+    }
+
+    None
+}
+
+impl fmt::Display for DependencyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown { test, dependency } => write!(
+                f,
+                "{test} depends on {dependency:?} which does not match any test",
+                test = test,
+                dependency = dependency
+            ),
+            Self::Ambiguous {
+                test,
+                dependency,
+                candidates,
+            } => write!(
+                f,
+                "{test} depends on {dependency:?} which matches more than one test: {candidates}",
+                test = test,
+                dependency = dependency,
+                candidates = candidates.join(", "),
+            ),
+            Self::Cycle { tests } => write!(
+                f,
+                "cycle in the dependencies: {cycle} -> {first}",
+                cycle = tests.join(" -> "),
+                first = tests.first().map(String::as_str).unwrap_or_default(),
+            ),
+        }
+    }
+}
+
+impl Error for DependencyError {}
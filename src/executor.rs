@@ -1,101 +1,1282 @@
-use crate::{test_tree::TestItem, Ordering, OrderingCondition, Settings, TestName, TestResult};
+use crate::{
+    capture,
+    context::Environment,
+    dependency::{self, DependencyError, Target},
+    filter::Filter,
+    fixture::Fixtures,
+    ignored::{Ignored, RunIgnored},
+    isolation::{self, Harness},
+    panics,
+    pool::WorkerPool,
+    repeat, retry,
+    rng::{self, Rng},
+    settings::{Backtrace, Capture, FailFast, Isolation, Jobs, Repeat, Retry, Setting, SkipIf},
+    shard::Shard,
+    tags::TagExpression,
+    test_tree::{self, TestItem, PATH_SEPARATOR},
+    CancellationToken, FailureReason, IsTest, Ordering, OrderingCondition, Outcome, Settings,
+    TestName, TestResult,
+};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::{mpsc, Arc},
+};
 
+#[derive(Debug, Clone)]
 pub enum TestedResult {
-    Single { name: TestName, result: TestResult },
-    GroupStart { name: TestName, ordering: Ordering },
-    GroupEnd { name: TestName, ordering: Ordering },
+    Single {
+        name: TestName,
+        result: TestResult,
+        /// the tags of the test and of its groups
+        tags: Vec<TestName>,
+    },
+    Setup {
+        name: TestName,
+        result: TestResult,
+    },
+    Teardown {
+        name: TestName,
+        result: TestResult,
+    },
+    GroupStart {
+        name: TestName,
+        ordering: Ordering,
+    },
+    GroupEnd {
+        name: TestName,
+        ordering: Ordering,
+    },
 }
 
-pub struct Executor<I> {
-    tests: I,
-    condition: Vec<(Ordering, bool)>,
-    settings: Vec<Settings>,
+/// execute the tests and yield their results
+///
+/// The results are always yielded in the order of the [`TestTree`]
+/// even if the tests of [`Ordering::Any`] groups are actually executed
+/// concurrently (see [`Jobs`]).
+///
+/// [`TestTree`]: crate::TestTree
+pub struct Executor {
+    steps: VecDeque<Step>,
+    jobs: Vec<Job>,
+    /// every job before this index is already started or completed
+    first_pending: usize,
+    /// the seed of the run, the seeds of the tests derive from it
+    seed: u64,
+    running: usize,
+    /// the tests dropped by [`Executor::prune`], see [`Plan::filtered_out`]
+    ///
+    /// [`Plan::filtered_out`]: crate::Plan::filtered_out
+    filtered_out: usize,
+    /// how to run the tests in their own process
+    harness: Harness,
+    /// `false` if the output of the tests cannot be captured
+    capture: bool,
+    /// a job capturing its output is running, it runs alone
+    capturing: bool,
+    /// the path of the test that failed first, see [`FailFast`]
+    failed_first: Option<String>,
+    run_ignored: RunIgnored,
+    pool: WorkerPool,
+    sender: mpsc::Sender<(usize, TestResult)>,
+    receiver: mpsc::Receiver<(usize, TestResult)>,
 }
 
-impl<I> Executor<I>
-where
-    I: Iterator<Item = TestItem>,
-{
-    pub fn new<II>(tests: II) -> Self
+/// the events to report, in the order of the test tree
+enum Step {
+    Job(usize),
+    GroupStart {
+        name: TestName,
+        ordering: Ordering,
+    },
+    GroupEnd {
+        name: TestName,
+        ordering: Ordering,
+        /// released once the group ended
+        fixtures: Arc<Fixtures>,
+    },
+}
+
+struct Job {
+    name: TestName,
+    kind: Kind,
+    /// the full path of the test, used to report dependency failures
+    path: String,
+    /// the tags of the job and of its groups
+    tags: Vec<TestName>,
+    /// why the job is not executed, if it is ignored or inside an
+    /// ignored group
+    ignored: Option<Ignored>,
+    test: Option<Box<dyn IsTest + Send>>,
+    settings: Settings,
+    /// jobs that need to be completed before this one can start
+    after: Vec<usize>,
+    /// jobs that need to be successful for this one to start at all
+    requires: Vec<usize>,
+    /// jobs explicitly declared as dependencies of this one, they need
+    /// to be successful too
+    dependencies: Vec<usize>,
+    /// see [`FailFast`]
+    fail_fast: bool,
+    /// the maximum number of jobs running when this one starts, see
+    /// [`Jobs`]
+    limit: usize,
+    state: State,
+}
+
+enum State {
+    Pending,
+    /// not selected to be executed, see [`Executor::prune`]
+    Pruned,
+    Running,
+    Done {
+        result: Option<TestResult>,
+        status: Status,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Test,
+    Setup,
+    Teardown,
+}
+
+/// how a completed job affects the jobs requiring it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    /// the job completed without failure, the jobs requiring it may run
+    Passed,
+    /// the job failed
+    Failed,
+    /// the job was skipped because one of its own requirement failed
+    Blocked,
+    /// the job is ignored, the jobs requiring it may run but not the
+    /// ones depending on it
+    Ignored,
+}
+
+/// flatten the tests into the events to report and the jobs to execute
+///
+/// this is where the settings of every test are resolved and where the
+/// ordering of the groups and the declared dependencies are translated
+/// into dependencies between the jobs.
+struct Planner {
+    steps: Vec<Step>,
+    jobs: Vec<Job>,
+    /// the dependencies declared on each job
+    declared: Vec<Vec<TestName>>,
+    targets: Vec<Target>,
+    frames: Vec<Frame>,
+    /// the seed of the run, given to the shuffled groups without one
+    seed: u64,
+}
+
+/// the group being planned
+struct Frame {
+    /// index of the first step of the group's content
+    start: usize,
+    path: String,
+    ordering: Ordering,
+    settings: Settings,
+    fixtures: Arc<Fixtures>,
+    /// dependencies declared on the group and its parents
+    dependencies: Vec<TestName>,
+    /// tags of the group and its parents
+    tags: Vec<TestName>,
+    /// why the group is ignored, if it or one of its parents is
+    ignored: Option<Ignored>,
+    setups: Vec<usize>,
+    teardowns: Vec<usize>,
+    children: Vec<Child>,
+}
+
+/// how the test of a job is run, maybe more than once
+enum Runner {
+    /// in a thread of the current process
+    Thread {
+        test: Option<Box<dyn IsTest + Send>>,
+        capture: bool,
+    },
+    /// in a child process, see [`Isolation::Process`]
+    Process {
+        harness: Harness,
+        path: String,
+        seed: u64,
+    },
+}
+
+/// a child of a group being planned
+struct Child {
+    /// index of the first step of the child
+    step: usize,
+    /// all the jobs of the child
+    jobs: Vec<usize>,
+}
+
+impl Executor {
+    /// prepare the execution of the given tests
+    ///
+    /// # panics
+    ///
+    /// if the dependencies between the tests are not valid, see
+    /// [`Executor::try_new`] to handle the error instead.
+    pub fn new<I>(tests: I) -> Self
     where
-        II: IntoIterator<IntoIter = I>,
+        I: IntoIterator<Item = TestItem>,
     {
-        Self {
-            tests: tests.into_iter(),
-            condition: Vec::new(),
-            settings: vec![Settings::new()],
+        match Self::try_new(tests) {
+            Ok(executor) => executor,
+            Err(error) => panic!("{}", error),
         }
     }
-}
 
-impl<I> Iterator for Executor<I>
-where
-    I: Iterator<Item = TestItem>,
-{
-    type Item = TestedResult;
-    fn next(&mut self) -> Option<Self::Item> {
-        let test = self.tests.next()?;
+    /// prepare the execution of the given tests
+    ///
+    /// The dependencies between the tests are resolved here so invalid
+    /// dependencies are reported before any of the test is executed.
+    ///
+    /// All the tests are executed, the seed of the run is drawn randomly.
+    /// See [`Executor::from_env`] to execute them as configured by the
+    /// environment of the process instead.
+    pub fn try_new<I>(tests: I) -> Result<Self, DependencyError>
+    where
+        I: IntoIterator<Item = TestItem>,
+    {
+        Self::plan(tests, rng::random_seed())
+    }
 
-        let result = match test {
-            TestItem::Single { test, name } => {
-                let skip = if let Some((_, failed)) = self.condition.last() {
-                    *failed
-                } else {
-                    false
-                };
+    /// prepare the execution of the given tests as configured by the
+    /// environment of the process
+    ///
+    /// This is what [`scaffold`] and [`TestTree::run`] use:
+    ///
+    /// * the seed of the run is taken from `SCAFFOLDING_SEED`, if set;
+    /// * the ignored tests are executed as given by [`RunIgnored::from_env`];
+    /// * only the tests selected by [`Filter::from_env`] and
+    ///   [`TagExpression::from_env`] are executed, if any;
+    /// * only the tests of the [`Shard::from_env`] are executed, if any.
+    ///
+    /// In the child process of a test isolated in its own process (see
+    /// [`Isolation::Process`]) this runs the test, reports its result to the
+    /// parent process and exits.
+    ///
+    /// # panics
+    ///
+    /// if the dependencies between the tests are not valid or if the
+    /// environment variables are not valid.
+    ///
+    /// [`scaffold`]: crate::scaffold()
+    /// [`TestTree::run`]: crate::TestTree::run
+    pub fn from_env<I>(tests: I) -> Self
+    where
+        I: IntoIterator<Item = TestItem>,
+    {
+        let seed = rng::seed_from_env().unwrap_or_else(rng::random_seed);
+        let executor = match Self::plan(tests, seed) {
+            Ok(executor) => executor,
+            Err(error) => panic!("{}", error),
+        };
 
-                if !skip {
-                    let settings = self.settings.last().cloned().unwrap_or_default();
-                    let result = test.run(settings);
-
-                    if let Some((Ordering::Sequence { on_condition }, failed)) =
-                        self.condition.last_mut()
-                    {
-                        if result.is_failure() && matches!(on_condition, OrderingCondition::Success)
-                        {
-                            *failed = true;
-                        }
-                    }
+        if let Some(path) = isolation::selected() {
+            executor.run_isolated(&path)
+        }
 
-                    TestedResult::Single { result, name }
-                } else {
-                    TestedResult::Single {
-                        result: TestResult::skip(),
-                        name,
-                    }
+        let executor = executor.run_ignored(RunIgnored::from_env());
+        let executor = match Filter::from_env() {
+            Some(filter) => executor.filter(&filter),
+            None => executor,
+        };
+        let executor = match TagExpression::from_env() {
+            Some(expression) => executor.filter_tags(&expression),
+            None => executor,
+        };
+        match Shard::from_env() {
+            Some(shard) => executor.shard(&shard),
+            None => executor,
+        }
+    }
+
+    fn plan<I>(tests: I, seed: u64) -> Result<Self, DependencyError>
+    where
+        I: IntoIterator<Item = TestItem>,
+    {
+        let (steps, jobs) = Planner::plan(tests, seed)?;
+        let (sender, receiver) = mpsc::channel();
+
+        Ok(Self {
+            steps,
+            jobs,
+            first_pending: 0,
+            seed,
+            running: 0,
+            filtered_out: 0,
+            harness: Harness::current(),
+            capture: capture::available(),
+            capturing: false,
+            failed_first: None,
+            run_ignored: RunIgnored::No,
+            pool: WorkerPool::new(),
+            sender,
+            receiver,
+        })
+    }
+
+    /// only execute the tests selected by the filter
+    ///
+    /// The dependencies of the selected tests are executed too, the groups
+    /// left without any test to execute are not executed nor reported.
+    pub fn filter(mut self, filter: &Filter) -> Self {
+        self.prune(|job| filter.matches(&job.path));
+        self
+    }
+
+    /// choose whether the ignored tests are executed
+    ///
+    /// With [`RunIgnored::Only`], the tests that are not ignored are not
+    /// executed nor reported, unless an ignored test depends on them.
+    /// The tests marked as todo are never executed.
+    pub fn run_ignored(mut self, mode: RunIgnored) -> Self {
+        self.run_ignored = mode;
+        if mode == RunIgnored::Only {
+            self.prune(|job| matches!(job.ignored, Some(Ignored::Reason(_))));
+        }
+        self
+    }
+
+    /// only execute the tests whose tags match the expression
+    ///
+    /// like [`Executor::filter`], the dependencies of the selected tests
+    /// are executed too.
+    pub fn filter_tags(mut self, expression: &TagExpression) -> Self {
+        self.prune(|job| expression.matches(&job.tags));
+        self
+    }
+
+    /// only execute the tests of the shard
+    ///
+    /// With [`Shard::balanced`], the tests are balanced among the tests
+    /// still selected by the other filters: they need to be the same on
+    /// every machine.
+    ///
+    /// The tests depending on each other, and the tests of the same
+    /// sequence or shuffled group, are executed on the same shard, so every
+    /// test is executed by exactly one shard and after the same tests as
+    /// without sharding.
+    pub fn shard(mut self, shard: &Shard) -> Self {
+        let is_test = |job: &Job| job.kind == Kind::Test && job.test.is_some();
+
+        // the tests linked by their dependencies or by the ordering of
+        // their group, each one pointing to the first test of its unit
+        let mut units: Vec<usize> = (0..self.jobs.len()).collect();
+        for (index, job) in self.jobs.iter().enumerate().filter(|(_, job)| is_test(job)) {
+            let linked = job
+                .dependencies
+                .iter()
+                .chain(&job.requires)
+                .chain(&job.after);
+            for &dependency in linked {
+                if is_test(&self.jobs[dependency]) {
+                    let (left, right) = (find(&mut units, index), find(&mut units, dependency));
+                    units[left.max(right)] = left.min(right);
+                }
+            }
+        }
+
+        let mut paths: Vec<Vec<&str>> = Vec::new();
+        let mut unit_of = HashMap::new();
+        for (index, job) in self.jobs.iter().enumerate().filter(|(_, job)| is_test(job)) {
+            let unit = *unit_of.entry(find(&mut units, index)).or_insert_with(|| {
+                paths.push(Vec::new());
+                paths.len() - 1
+            });
+            paths[unit].push(job.path.as_str());
+        }
+
+        let selected: HashSet<String> = shard
+            .select(&paths)
+            .into_iter()
+            .map(str::to_owned)
+            .collect();
+        self.prune(|job| selected.contains(&job.path));
+        self
+    }
+
+    /// drop the tests that are not selected and the groups left empty
+    ///
+    /// the jobs dropped still complete once the jobs they come after
+    /// completed, without running. They pass on whether these jobs
+    /// succeeded so a job ordered after them is held back as if they ran.
+    fn prune<F>(&mut self, selected: F)
+    where
+        F: Fn(&Job) -> bool,
+    {
+        // the groups containing every job, as the steps starting them
+        let mut groups = vec![Vec::new(); self.jobs.len()];
+        let mut stack = Vec::new();
+        for (index, step) in self.steps.iter().enumerate() {
+            match step {
+                Step::GroupStart { .. } => stack.push(index),
+                Step::GroupEnd { .. } => {
+                    stack.pop();
                 }
+                Step::Job(job) => groups[*job] = stack.clone(),
             }
-            TestItem::GroupStart { name, ordering } => {
-                let skip = if let Some((ordering, failed)) = self.condition.last() {
-                    match ordering {
-                        Ordering::Any => false,
-                        Ordering::Sequence { on_condition } => match on_condition {
-                            OrderingCondition::Finish => false,
-                            OrderingCondition::Success => *failed,
-                        },
+        }
+
+        let mut kept: Vec<bool> = self
+            .jobs
+            .iter()
+            .map(|job| job.kind == Kind::Test && selected(job))
+            .collect();
+        let mut kept_groups = HashSet::new();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for index in 0..self.jobs.len() {
+                if kept[index] {
+                    kept_groups.extend(groups[index].iter().copied());
+                    for &dependency in &self.jobs[index].dependencies {
+                        changed |= !kept[dependency];
+                        kept[dependency] = true;
                     }
+                }
+            }
+            // the setups and teardowns of the groups still executed
+            for index in 0..self.jobs.len() {
+                let in_kept_group = groups[index]
+                    .last()
+                    .is_some_and(|group| kept_groups.contains(group));
+                if !kept[index] && self.jobs[index].kind != Kind::Test && in_kept_group {
+                    kept[index] = true;
+                    changed = true;
+                }
+            }
+        }
+
+        for (job, &kept) in self.jobs.iter_mut().zip(&kept) {
+            if !kept && !matches!(job.state, State::Pruned) {
+                if job.kind == Kind::Test {
+                    self.filtered_out += 1;
+                }
+                job.test = None;
+                job.state = State::Pruned;
+            }
+        }
+
+        let mut stack = Vec::new();
+        let steps = std::mem::take(&mut self.steps);
+        for (index, step) in steps.into_iter().enumerate() {
+            let keep = match &step {
+                Step::Job(job) => kept[*job],
+                Step::GroupStart { .. } => {
+                    stack.push(kept_groups.contains(&index));
+                    kept_groups.contains(&index)
+                }
+                Step::GroupEnd { .. } => stack.pop().unwrap_or(false),
+            };
+            if keep {
+                self.steps.push_back(step);
+            }
+        }
+    }
+
+    /// run the test selected by the parent process and report its result
+    ///
+    /// this is the child process side of [`Isolation::Process`], the
+    /// setups of the test's groups are run first for their fixtures and
+    /// their teardowns last, innermost first, to release what the setups
+    /// acquired.
+    fn run_isolated(mut self, path: &str) -> ! {
+        // the output is captured by the parent process
+        capture::disable();
+        let index = self
+            .jobs
+            .iter()
+            .position(|job| job.kind == Kind::Test && job.path == path)
+            .unwrap_or_else(|| panic!("no test {path} to run in its own process", path = path));
+
+        let mut setups: Vec<usize> = self.jobs[index]
+            .dependencies
+            .iter()
+            .copied()
+            .filter(|&dependency| self.jobs[dependency].kind == Kind::Setup)
+            .collect();
+        setups.sort_unstable();
+        let mut teardowns: Vec<usize> = (0..self.jobs.len())
+            .filter(|&teardown| {
+                self.jobs[teardown].kind == Kind::Teardown
+                    && self.jobs[teardown].after.contains(&index)
+            })
+            .collect();
+        teardowns.sort_by_key(|&teardown| {
+            let depth = self.jobs[teardown].path.matches(PATH_SEPARATOR).count();
+            (std::cmp::Reverse(depth), teardown)
+        });
+
+        let mut result = None;
+        for setup in setups {
+            let (test, settings) = self.prepare(setup);
+            if run(test, settings, false).0.is_failure() {
+                result = Some(TestResult::failed(format!(
+                    "setup {} failed in the process of the test",
+                    self.jobs[setup].path
+                )));
+                break;
+            }
+        }
+        let result = result.unwrap_or_else(|| {
+            let (test, settings) = self.prepare(index);
+            run(test, settings, false).0
+        });
+
+        // the teardowns are reported by the parent process, which runs
+        // them too
+        for teardown in teardowns {
+            let (test, settings) = self.prepare(teardown);
+            run(test, settings, false);
+        }
+        isolation::report(&result)
+    }
+
+    /// the number of tests left to report
+    pub(crate) fn tests(&self) -> usize {
+        self.steps
+            .iter()
+            .filter(|step| matches!(step, Step::Job(index) if self.jobs[*index].kind == Kind::Test))
+            .count()
+    }
+
+    /// the number of tests dropped from the run by the filters
+    pub(crate) fn filtered_out(&self) -> usize {
+        self.filtered_out
+    }
+
+    /// the name of the test whose result comes next, if the next result is
+    /// the one of a test
+    pub(crate) fn upcoming_test(&self) -> Option<&TestName> {
+        match self.steps.front()? {
+            Step::Job(index) if self.jobs[*index].kind == Kind::Test => {
+                Some(&self.jobs[*index].name)
+            }
+            _ => None,
+        }
+    }
+
+    /// block until the result of the given job is available
+    fn wait(&mut self, index: usize) -> TestResult {
+        loop {
+            while self.schedule() {}
+
+            if let State::Done { result, .. } = &mut self.jobs[index].state {
+                return result
+                    .take()
+                    .expect("the result of a test is only reported once");
+            }
+
+            assert!(
+                self.running > 0,
+                "no test is running, the test {name} will never be completed",
+                name = self.jobs[index].name,
+            );
+
+            // we hold a sender too, receiving cannot fail
+            let (completed, result) = self.receiver.recv().unwrap();
+            self.running -= 1;
+            if self.running == 0 {
+                self.capturing = false;
+            }
+            let job = &self.jobs[completed];
+            if job.fail_fast && result.is_failure() && self.failed_first.is_none() {
+                self.failed_first = Some(job.path.clone());
+            }
+            self.jobs[completed].state = State::Done {
+                status: if result.is_failure() {
+                    Status::Failed
                 } else {
-                    false
+                    Status::Passed
+                },
+                result: Some(result),
+            };
+        }
+    }
+
+    /// start every job that is ready to run
+    ///
+    /// returns `true` if some jobs changed of state
+    fn schedule(&mut self) -> bool {
+        while let Some(job) = self.jobs.get(self.first_pending) {
+            if matches!(job.state, State::Pending | State::Pruned) {
+                break;
+            }
+            self.first_pending += 1;
+        }
+
+        let mut progress = false;
+        for index in self.first_pending..self.jobs.len() {
+            let job = &self.jobs[index];
+            match job.state {
+                State::Pending => (),
+                State::Pruned => {
+                    progress |= self.complete_pruned(index);
+                    continue;
+                }
+                State::Running | State::Done { .. } => continue,
+            }
+
+            if let Some(ignored) = &job.ignored {
+                if !self.run_ignored.runs(ignored) {
+                    self.jobs[index].state = State::Done {
+                        result: Some(ignored.result()),
+                        status: Status::Ignored,
+                    };
+                    progress = true;
+                    continue;
+                }
+            }
+
+            if let Some(path) = &self.failed_first {
+                if job.fail_fast && job.kind != Kind::Teardown {
+                    self.jobs[index].state = State::Done {
+                        result: Some(TestResult {
+                            details: format!("not run: fail-fast triggered by {}", path).into(),
+                            ..TestResult::skip()
+                        }),
+                        status: Status::Blocked,
+                    };
+                    progress = true;
+                    continue;
+                }
+            }
+
+            let mut dependencies = job
+                .after
+                .iter()
+                .chain(&job.requires)
+                .chain(&job.dependencies);
+            if !dependencies.all(|&dependency| self.jobs[dependency].status().is_some()) {
+                continue;
+            }
+
+            let skip = if !job.requires.iter().all(|&dependency| {
+                matches!(
+                    self.jobs[dependency].status(),
+                    Some(Status::Passed | Status::Ignored)
+                )
+            }) {
+                Some(TestResult::skip())
+            } else {
+                job.dependencies.iter().find_map(|&dependency| {
+                    let dependency = &self.jobs[dependency];
+                    let kind = match dependency.kind {
+                        Kind::Setup => "setup",
+                        Kind::Test | Kind::Teardown => "dependency",
+                    };
+                    let details = match dependency.status()? {
+                        Status::Passed => return None,
+                        Status::Failed => format!("{} {} failed", kind, dependency.path),
+                        Status::Blocked => format!("{} {} was not run", kind, dependency.path),
+                        Status::Ignored => format!("{} {} is ignored", kind, dependency.path),
+                    };
+                    Some(TestResult {
+                        details: details.into(),
+                        ..TestResult::skip()
+                    })
+                })
+            };
+            if let Some(result) = skip {
+                self.jobs[index].state = State::Done {
+                    result: Some(result),
+                    status: Status::Blocked,
                 };
+                progress = true;
+                continue;
+            }
 
-                self.condition.push((ordering, skip));
-                self.settings
-                    .push(self.settings.last().cloned().unwrap_or_default());
-                TestedResult::GroupStart { name, ordering }
+            // the output is captured for the whole process, a job capturing
+            // it waits for the running jobs and no job starts until it is
+            // completed
+            if self.capturing {
+                break;
+            }
+            let captures = self.captures(job);
+            if captures && self.running > 0 {
+                break;
             }
-            TestItem::SetSetting { value } => {
-                if let Some(settings) = self.settings.last_mut() {
-                    settings.insert_raw(value);
 
-                    // TODO: bad
-                    self.next()?
-                } else {
-                    unreachable!("there should always be at least one setting")
+            let limit = job.limit;
+            if self.running >= limit {
+                continue;
+            }
+
+            self.capturing = captures;
+            self.start(index, limit);
+            progress = true;
+        }
+
+        progress
+    }
+
+    /// complete the pruned job if the jobs it comes after completed
+    ///
+    /// returns `true` if the job completed
+    fn complete_pruned(&mut self, index: usize) -> bool {
+        let job = &self.jobs[index];
+        let mut dependencies = job
+            .after
+            .iter()
+            .chain(&job.requires)
+            .chain(&job.dependencies);
+        if !dependencies.all(|&dependency| self.jobs[dependency].status().is_some()) {
+            return false;
+        }
+
+        let passed = job.requires.iter().all(|&dependency| {
+            matches!(
+                self.jobs[dependency].status(),
+                Some(Status::Passed | Status::Ignored)
+            )
+        });
+        self.jobs[index].state = State::Done {
+            result: None,
+            status: if passed {
+                Status::Passed
+            } else {
+                Status::Blocked
+            },
+        };
+        true
+    }
+
+    /// `true` if the output of the job is captured while it runs
+    fn captures(&self, job: &Job) -> bool {
+        self.capture
+            && *job.settings.get_or_default::<Capture>() != Capture::Off
+            && !(job.kind == Kind::Test
+                && *job.settings.get_or_default::<Isolation>() == Isolation::Process)
+    }
+
+    fn start(&mut self, index: usize, workers: usize) {
+        let (test, settings) = self.prepare(index);
+        let job = &mut self.jobs[index];
+        let mut runner = if job.kind == Kind::Test
+            && *settings.get_or_default::<Isolation>() == Isolation::Process
+        {
+            Runner::Process {
+                harness: self.harness.clone(),
+                path: job.path.clone(),
+                seed: self.seed,
+            }
+        } else {
+            Runner::Thread {
+                test: Some(test),
+                capture: self.capture,
+            }
+        };
+        let (repetitions, attempts, skip_if) = if job.kind == Kind::Test {
+            (
+                *settings.get_or_default::<Repeat>(),
+                *settings.get_or_default::<Retry>(),
+                settings.get::<SkipIf>(),
+            )
+        } else {
+            (Repeat::default(), Retry::default(), None)
+        };
+        let sender = self.sender.clone();
+
+        job.state = State::Running;
+        self.running += 1;
+        self.pool.grow(workers);
+        self.pool.execute(move || {
+            let skip = skip_if.map(|skip_if| panics::catch(Backtrace::Off, || skip_if.reason()));
+            let result = match skip {
+                Some(Ok(Some(reason))) => TestResult {
+                    details: reason.into(),
+                    ..TestResult::skip()
+                },
+                Some(Err(details)) => TestResult::failed(format!(
+                    "the condition to skip the test failed:\n{}",
+                    details
+                )),
+                Some(Ok(None)) | None => repeat::repeat(repetitions, || {
+                    retry::retry(attempts, || runner.attempt(&settings))
+                })
+                .expect("a test runs at least once"),
+            };
+            let _ = sender.send((index, result));
+        });
+    }
+
+    /// take the test of the job and the settings to run it with
+    fn prepare(&mut self, index: usize) -> (Box<dyn IsTest + Send>, Settings) {
+        let job = &mut self.jobs[index];
+        let test = job.test.take().expect("a test is only started once");
+        let mut settings = job.settings.clone();
+        settings.insert(Environment {
+            path: job.path.clone(),
+            seed: self.seed,
+            cancellation: CancellationToken::new(),
+        });
+        (test, settings)
+    }
+}
+
+impl Runner {
+    /// run the test once, returns `None` if the test cannot run anymore
+    fn attempt(&mut self, settings: &Settings) -> Option<TestResult> {
+        match self {
+            Self::Thread { test, capture } => {
+                // every attempt has its own cancellation token
+                let mut settings = settings.clone();
+                if let Some(environment) = settings.get::<Environment>() {
+                    settings.insert(Environment {
+                        path: environment.path.clone(),
+                        seed: environment.seed,
+                        cancellation: CancellationToken::new(),
+                    });
+                }
+
+                let (result, again) = run(test.take()?, settings, *capture);
+                *test = again;
+                Some(result)
+            }
+            Self::Process {
+                harness,
+                path,
+                seed,
+            } => Some(harness.run(path, *seed, settings)),
+        }
+    }
+}
+
+/// run the test once in the current process
+///
+/// returns the test if it can run again.
+fn run(
+    test: Box<dyn IsTest + Send>,
+    settings: Settings,
+    capture: bool,
+) -> (TestResult, Option<Box<dyn IsTest + Send>>) {
+    let mode = *settings.get_or_default::<Capture>();
+    let guard = if capture && mode != Capture::Off {
+        capture::start()
+    } else {
+        None
+    };
+
+    let backtrace = *settings.get_or_default::<Backtrace>();
+    let (result, test) =
+        panics::catch(backtrace, || test.run_and_keep(settings)).unwrap_or_else(|details| {
+            let result = TestResult {
+                outcome: Outcome::Failure {
+                    reason: FailureReason::Panicked,
+                },
+                ..TestResult::failed(details)
+            };
+            (result, None)
+        });
+
+    let result = match guard {
+        Some(guard) => captured(result, guard.finish(), mode),
+        None => result,
+    };
+    (result, test)
+}
+
+/// attach the captured output to the result, depending on the [`Capture`] mode
+pub(crate) fn captured(result: TestResult, output: String, mode: Capture) -> TestResult {
+    if mode == Capture::On || (mode == Capture::OnFailure && result.is_failure()) {
+        TestResult {
+            output: output.into(),
+            ..result
+        }
+    } else {
+        result
+    }
+}
+
+impl Job {
+    /// the status of the job, if completed
+    fn status(&self) -> Option<Status> {
+        if let State::Done { status, .. } = self.state {
+            Some(status)
+        } else {
+            None
+        }
+    }
+}
+
+impl Planner {
+    fn plan<I>(tests: I, seed: u64) -> Result<(VecDeque<Step>, Vec<Job>), DependencyError>
+    where
+        I: IntoIterator<Item = TestItem>,
+    {
+        let fixtures = Fixtures::root();
+        let mut settings = Settings::new();
+        settings.insert_raw(Arc::clone(&fixtures) as Arc<dyn Setting>);
+
+        let mut planner = Self {
+            steps: Vec::new(),
+            jobs: Vec::new(),
+            declared: Vec::new(),
+            targets: Vec::new(),
+            frames: vec![Frame {
+                start: 0,
+                path: String::new(),
+                ordering: Ordering::Sequence {
+                    on_condition: OrderingCondition::Finish,
+                },
+                settings,
+                fixtures,
+                dependencies: Vec::new(),
+                tags: Vec::new(),
+                ignored: None,
+                setups: Vec::new(),
+                teardowns: Vec::new(),
+                children: Vec::new(),
+            }],
+            seed,
+        };
+
+        for test in tests {
+            match test {
+                TestItem::Single {
+                    name,
+                    test,
+                    dependencies,
+                    tags,
+                    ignored,
+                } => planner.single(name, test, dependencies, tags, ignored),
+                TestItem::SetSetting { value } => planner.frame().settings.insert_raw(value),
+                TestItem::Setup { name, test } => {
+                    let index = planner.job(Kind::Setup, name, test, Vec::new(), Vec::new(), None);
+                    planner.frame().setups.push(index);
+                }
+                TestItem::Teardown { name, test } => {
+                    let index =
+                        planner.job(Kind::Teardown, name, test, Vec::new(), Vec::new(), None);
+                    planner.frame().teardowns.push(index);
+                }
+                TestItem::GroupStart {
+                    name,
+                    ordering,
+                    dependencies,
+                    tags,
+                    ignored,
+                } => planner.group_start(name, ordering, dependencies, tags, ignored),
+                TestItem::GroupEnd { name, .. } => planner.group_end(name),
+            }
+        }
+
+        planner.finish()
+    }
+
+    fn frame(&mut self) -> &mut Frame {
+        self.frames
+            .last_mut()
+            .expect("there should always be at least one frame")
+    }
+
+    /// add a new job in the current group, the caller is responsible
+    /// for adding the job's step
+    fn job(
+        &mut self,
+        kind: Kind,
+        name: TestName,
+        test: Box<dyn IsTest + Send>,
+        dependencies: Vec<TestName>,
+        tags: Vec<TestName>,
+        ignored: Option<Ignored>,
+    ) -> usize {
+        let frame = self.frame();
+        let path = frame.path_of(&name);
+        let settings = frame.settings.clone();
+        let dependencies = [frame.dependencies.as_slice(), &dependencies].concat();
+        let tags = merge_tags(&frame.tags, tags);
+        let ignored = ignored.or_else(|| frame.ignored.clone());
+        let FailFast(fail_fast) = *settings.get_or_default::<FailFast>();
+        // resolved once, the default is the parallelism of the machine
+        let Jobs(limit) = *settings.get_or_default::<Jobs>();
+
+        let index = self.jobs.len();
+        self.jobs.push(Job {
+            name,
+            kind,
+            path,
+            tags,
+            ignored,
+            test: Some(test),
+            settings,
+            after: Vec::new(),
+            requires: Vec::new(),
+            dependencies: Vec::new(),
+            fail_fast,
+            limit: limit.max(1),
+            state: State::Pending,
+        });
+        self.declared.push(dependencies);
+        self.targets.push(Target {
+            path: self.jobs[index].path.clone(),
+            jobs: vec![index],
+        });
+        index
+    }
+
+    fn single(
+        &mut self,
+        name: TestName,
+        test: Box<dyn IsTest + Send>,
+        dependencies: Vec<TestName>,
+        tags: Vec<TestName>,
+        ignored: Option<Ignored>,
+    ) {
+        let index = self.job(Kind::Test, name, test, dependencies, tags, ignored);
+        let step = self.steps.len();
+        self.frame().children.push(Child {
+            step,
+            jobs: vec![index],
+        });
+        self.steps.push(Step::Job(index));
+    }
+
+    fn group_start(
+        &mut self,
+        name: TestName,
+        ordering: Ordering,
+        dependencies: Vec<TestName>,
+        tags: Vec<TestName>,
+        ignored: Option<Ignored>,
+    ) {
+        let ordering = match ordering {
+            Ordering::Shuffled { seed: None } => Ordering::Shuffled {
+                seed: Some(self.seed),
+            },
+            ordering => ordering,
+        };
+
+        let start = self.steps.len() + 1;
+        let parent = self.frame();
+        let fixtures = Fixtures::scope(&parent.fixtures);
+        let mut settings = parent.settings.clone();
+        settings.insert_raw(Arc::clone(&fixtures) as Arc<dyn Setting>);
+        let frame = Frame {
+            start,
+            path: parent.path_of(&name),
+            ordering,
+            settings,
+            fixtures,
+            dependencies: [parent.dependencies.as_slice(), &dependencies].concat(),
+            tags: merge_tags(&parent.tags, tags),
+            ignored: ignored.or_else(|| parent.ignored.clone()),
+            setups: Vec::new(),
+            teardowns: Vec::new(),
+            children: Vec::new(),
+        };
+        self.frames.push(frame);
+        self.steps.push(Step::GroupStart { name, ordering });
+    }
+
+    fn group_end(&mut self, name: TestName) {
+        let mut frame = self
+            .frames
+            .pop()
+            .expect("group ended without being started");
+        let step = frame.start - 1;
+        let ordering = frame.ordering;
+        let fixtures = Arc::clone(&frame.fixtures);
+        let path = std::mem::take(&mut frame.path);
+
+        let jobs = frame.finish(&mut self.steps, &mut self.jobs);
+        self.targets.push(Target {
+            path,
+            jobs: jobs.clone(),
+        });
+        self.frame().children.push(Child { step, jobs });
+        self.steps.push(Step::GroupEnd {
+            name,
+            ordering,
+            fixtures,
+        });
+    }
+
+    fn finish(mut self) -> Result<(VecDeque<Step>, Vec<Job>), DependencyError> {
+        if let Some(root) = self.frames.pop() {
+            root.finish(&mut self.steps, &mut self.jobs);
+        }
+
+        let Self {
+            steps,
+            mut jobs,
+            declared,
+            targets,
+            ..
+        } = self;
+
+        for (index, declared) in declared.into_iter().enumerate() {
+            for dependency in declared {
+                let target = dependency::resolve(&targets, &jobs[index].path, &dependency)?;
+                jobs[index].dependencies.extend_from_slice(&target.jobs);
+            }
+        }
+
+        let cycle = dependency::find_cycle(jobs.len(), |index| {
+            let job = &jobs[index];
+            job.after
+                .iter()
+                .chain(&job.requires)
+                .chain(&job.dependencies)
+        });
+        if let Some(cycle) = cycle {
+            return Err(DependencyError::Cycle {
+                tests: cycle
+                    .into_iter()
+                    .map(|index| jobs[index].path.clone())
+                    .collect(),
+            });
+        }
+
+        Ok((steps.into(), jobs))
+    }
+}
+
+/// the first job of the unit of the given job, see [`Executor::shard`]
+fn find(units: &mut [usize], mut index: usize) -> usize {
+    while units[index] != index {
+        units[index] = units[units[index]];
+        index = units[index];
+    }
+    index
+}
+
+/// the tags of a group followed by the tags of its child, without duplicates
+fn merge_tags(parent: &[TestName], tags: Vec<TestName>) -> Vec<TestName> {
+    let mut merged = parent.to_vec();
+    for tag in tags {
+        if !merged.contains(&tag) {
+            merged.push(tag);
+        }
+    }
+    merged
+}
+
+impl Frame {
+    /// the path of a child of this group
+    fn path_of(&self, name: &str) -> String {
+        test_tree::join(&self.path, name)
+    }
+
+    /// put the steps of the group's content in the order they will be
+    /// reported and link the jobs of the group together
+    ///
+    /// returns all the jobs of the group
+    fn finish(mut self, steps: &mut Vec<Step>, jobs: &mut [Job]) -> Vec<usize> {
+        self.shuffle(steps);
+
+        // the setups are reported first and the teardowns last, wherever
+        // they were declared in the group
+        let setups = self.setups.iter().map(|&index| Step::Job(index));
+        steps.splice(self.start..self.start, setups);
+        steps.extend(self.teardowns.iter().map(|&index| Step::Job(index)));
+
+        self.link(jobs)
+    }
+
+    /// reorder the children of a shuffled group, both their steps
+    /// and the order they will be linked in
+    ///
+    /// the steps of the frame are always the last ones. The order derives
+    /// from the seed and the path of the group, so the groups sharing the
+    /// seed of the run are not all shuffled the same way.
+    fn shuffle(&mut self, steps: &mut Vec<Step>) {
+        if let Ordering::Shuffled { seed: Some(seed) } = self.ordering {
+            let mut content: Vec<Vec<Step>> = Vec::with_capacity(self.children.len());
+            for child in self.children.iter().rev() {
+                content.push(steps.split_off(child.step));
+            }
+            content.reverse();
+
+            let mut order: Vec<usize> = (0..self.children.len()).collect();
+            Rng::new(rng::derive(seed, &self.path)).shuffle(&mut order);
+
+            let mut children: Vec<Option<Child>> = self.children.drain(..).map(Some).collect();
+            for index in order {
+                let mut child = children[index].take().unwrap();
+                child.step = steps.len();
+                steps.append(&mut content[index]);
+                self.children.push(child);
+            }
+        }
+    }
+
+    /// add the dependencies between the jobs of the group and
+    /// returns all the jobs of the group
+    fn link(self, jobs: &mut [Job]) -> Vec<usize> {
+        let on_condition = match self.ordering {
+            Ordering::Any => None,
+            Ordering::Sequence { on_condition } => Some(on_condition),
+            Ordering::Shuffled { .. } => Some(OrderingCondition::Finish),
+        };
+
+        if let Some(on_condition) = on_condition {
+            let mut previous: &[usize] = &[];
+            for child in self.children.iter().filter(|child| !child.jobs.is_empty()) {
+                for &index in &child.jobs {
+                    let job = &mut jobs[index];
+                    match on_condition {
+                        OrderingCondition::Success => job.requires.extend_from_slice(previous),
+                        OrderingCondition::Finish => job.after.extend_from_slice(previous),
+                    }
+                }
+                previous = &child.jobs;
+            }
+        }
+
+        // the setups are executed one after the other and only if the
+        // previous ones succeeded, then the content of the group
+        let mut group = Vec::with_capacity(self.setups.len());
+        for &setup in &self.setups {
+            jobs[setup].dependencies.extend_from_slice(&group);
+            group.push(setup);
+        }
+        for index in self.children.into_iter().flat_map(|child| child.jobs) {
+            jobs[index].dependencies.extend_from_slice(&self.setups);
+            group.push(index);
+        }
+
+        // the teardowns are always executed, once everything else completed
+        for &teardown in &self.teardowns {
+            jobs[teardown].after.extend_from_slice(&group);
+            group.push(teardown);
+        }
+
+        group
+    }
+}
+
+impl Iterator for Executor {
+    type Item = TestedResult;
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = match self.steps.pop_front()? {
+            Step::Job(index) => {
+                let result = self.wait(index);
+                let name = self.jobs[index].name.clone();
+                match self.jobs[index].kind {
+                    Kind::Test => TestedResult::Single {
+                        name,
+                        result,
+                        tags: self.jobs[index].tags.clone(),
+                    },
+                    Kind::Setup => TestedResult::Setup { name, result },
+                    Kind::Teardown => TestedResult::Teardown { name, result },
                 }
             }
-            TestItem::GroupEnd { name, ordering } => {
-                let _ = self.condition.pop();
-                let _ = self.settings.pop();
+            Step::GroupStart { name, ordering } => TestedResult::GroupStart { name, ordering },
+            Step::GroupEnd {
+                name,
+                ordering,
+                fixtures,
+            } => {
+                // all the tests of the group completed
+                fixtures.clear();
                 TestedResult::GroupEnd { name, ordering }
             }
         };
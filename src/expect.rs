@@ -0,0 +1,131 @@
+use crate::{
+    filter, panics, settings::Backtrace, FailureReason, IsTest, Outcome, Settings, TestResult,
+};
+use std::borrow::Cow;
+
+/// how a test marked with [`TestTree::should_fail`] or
+/// [`TestTree::should_panic`] is expected to fail
+///
+/// [`TestTree::should_fail`]: crate::TestTree::should_fail
+/// [`TestTree::should_panic`]: crate::TestTree::should_panic
+#[derive(Debug, Clone)]
+pub(crate) enum Expectation {
+    /// any failure
+    Failure,
+    /// a panic, with a message matching the pattern if any
+    Panic(Option<String>),
+}
+
+/// a test expected to fail, its result is turned into a success if it
+/// failed as expected and into a failure if it did not
+pub(crate) struct Expect {
+    pub(crate) test: Box<dyn IsTest + Send>,
+    pub(crate) expectation: Expectation,
+}
+
+impl IsTest for Expect {
+    fn run(self: Box<Self>, settings: Settings) -> TestResult {
+        self.run_and_keep(settings).0
+    }
+
+    fn run_and_keep(
+        self: Box<Self>,
+        settings: Settings,
+    ) -> (TestResult, Option<Box<dyn IsTest + Send>>) {
+        let Self { test, expectation } = *self;
+        let backtrace = *settings.get_or_default::<Backtrace>();
+        let (result, test) = panics::catch(backtrace, || test.run_and_keep(settings))
+            .unwrap_or_else(|details| {
+                let result = TestResult {
+                    outcome: Outcome::Failure {
+                        reason: FailureReason::Panicked,
+                    },
+                    ..TestResult::failed(details)
+                };
+                (result, None)
+            });
+
+        let result = expectation.check(result);
+        let test = test.map(|test| Box::new(Self { test, expectation }) as Box<dyn IsTest + Send>);
+        (result, test)
+    }
+}
+
+impl Expectation {
+    fn check(&self, result: TestResult) -> TestResult {
+        if result.is_success() {
+            let expected = match self {
+                Self::Failure => "the test was expected to fail",
+                Self::Panic(_) => "the test was expected to panic",
+            };
+            return TestResult {
+                outcome: Outcome::Failure {
+                    reason: FailureReason::UnexpectedPass,
+                },
+                short: Cow::Borrowed("XPass"),
+                details: expected.into(),
+                ..result
+            };
+        }
+        if !result.is_failure() {
+            return result;
+        }
+
+        let panicked = matches!(
+            result.outcome,
+            Outcome::Failure {
+                reason: FailureReason::Panicked
+            }
+        );
+        let mismatch = match self {
+            Self::Failure => None,
+            Self::Panic(_) if !panicked => Some("the test was expected to panic".to_owned()),
+            Self::Panic(None) => None,
+            Self::Panic(Some(pattern)) => {
+                let message = message(&result.details);
+                let matches = if pattern.contains(['*', '?']) {
+                    filter::glob(pattern, message)
+                } else {
+                    message.contains(pattern.as_str())
+                };
+                (!matches).then(|| {
+                    format!(
+                        "the test was expected to panic with a message matching {:?}",
+                        pattern
+                    )
+                })
+            }
+        };
+
+        match mismatch {
+            Some(mismatch) => TestResult {
+                details: format!("{}\n{}", mismatch, result.details)
+                    .trim_end()
+                    .to_owned()
+                    .into(),
+                ..result
+            },
+            None => TestResult {
+                outcome: Outcome::Success,
+                short: Cow::Borrowed("XFail"),
+                ..result
+            },
+        }
+    }
+}
+
+/// the message of the panic described in the details of the result
+///
+/// see `panics::catch` for the description of the panics, it may be
+/// preceded by the logs of the test.
+fn message(details: &str) -> &str {
+    let description = details
+        .rfind("test panicked")
+        .map_or(details, |start| &details[start..]);
+    let message = description
+        .split_once('\n')
+        .map_or(description, |(_, message)| message);
+    message
+        .split_once("\n\nstack backtrace:\n")
+        .map_or(message, |(message, _)| message)
+}
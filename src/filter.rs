@@ -0,0 +1,170 @@
+use crate::isolation;
+use std::{env, thread};
+
+/// environment variable selecting the tests to run, see [`Filter`]
+pub(crate) const FILTER_ENV: &str = "SCAFFOLDING_FILTER";
+
+/// the options of the test harness followed by a value, when the value
+/// is not given after a `=`
+const OPTIONS_WITH_VALUE: &[&str] = &[
+    "--color",
+    "--format",
+    "--logfile",
+    "--shuffle-seed",
+    "--skip",
+    "--test-threads",
+    "-Z",
+];
+
+/// selects the tests to run by their path
+///
+/// The path of a test is made of the names of its groups and its own name,
+/// joined with `::`, like `root::group::test`. A test is selected if any
+/// of the patterns matches its path, a pattern can be:
+///
+/// * `=root::group::test` to match this exact path
+/// * a glob like `root::*::test`, where `*` matches any sequence of
+///   characters and `?` matches exactly one
+/// * anything else to match the paths containing it
+///
+/// The groups left without any selected test are not run nor reported.
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    patterns: Vec<Pattern>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Pattern {
+    Substring(String),
+    Exact(String),
+    Glob(String),
+}
+
+impl Filter {
+    /// select the tests matching any of the patterns
+    pub fn new<I, S>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            patterns: patterns
+                .into_iter()
+                .map(|pattern| Pattern::parse(pattern.as_ref(), false))
+                .collect(),
+        }
+    }
+
+    /// the filter given to the process, if any
+    ///
+    /// The patterns are the comma separated patterns of the
+    /// `SCAFFOLDING_FILTER` environment variable and the arguments of the
+    /// process. When running under the test harness (`cargo test`) the
+    /// arguments also select the `#[scaffold]` functions to run: the
+    /// ones matching the name of the running function are not patterns,
+    /// `cargo test -- my_scaffold login` runs the tests of `my_scaffold`
+    /// having `login` in their path. `--exact` makes the plain patterns
+    /// of the arguments exact.
+    pub fn from_env() -> Option<Self> {
+        let mut patterns: Vec<Pattern> = env::var(FILTER_ENV)
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|pattern| !pattern.is_empty())
+            .map(|pattern| Pattern::parse(pattern, false))
+            .collect();
+
+        let arguments: Vec<String> = env::args().skip(1).collect();
+        let exact = arguments.iter().any(|argument| argument == "--exact");
+        let current = thread::current();
+        let libtest = current.name().filter(|&name| name != "main");
+
+        let mut arguments = arguments.iter();
+        while let Some(argument) = arguments.next() {
+            if argument.starts_with('-') {
+                if OPTIONS_WITH_VALUE.contains(&argument.as_str()) {
+                    arguments.next();
+                }
+                continue;
+            }
+            if argument.starts_with(isolation::ARGUMENT) {
+                continue;
+            }
+            let selects_function = libtest.is_some_and(|name| {
+                if exact {
+                    name == argument
+                } else {
+                    name.contains(argument.as_str())
+                }
+            });
+            if !selects_function {
+                patterns.push(Pattern::parse(argument, exact));
+            }
+        }
+
+        if patterns.is_empty() {
+            None
+        } else {
+            Some(Self { patterns })
+        }
+    }
+
+    /// returns `true` if the test at the given path is selected
+    pub fn matches(&self, path: &str) -> bool {
+        self.patterns.iter().any(|pattern| pattern.matches(path))
+    }
+}
+
+impl Pattern {
+    fn parse(pattern: &str, exact: bool) -> Self {
+        if let Some(path) = pattern.strip_prefix('=') {
+            Self::Exact(path.to_owned())
+        } else if pattern.contains(['*', '?']) {
+            Self::Glob(pattern.to_owned())
+        } else if exact {
+            Self::Exact(pattern.to_owned())
+        } else {
+            Self::Substring(pattern.to_owned())
+        }
+    }
+
+    fn matches(&self, path: &str) -> bool {
+        match self {
+            Self::Substring(pattern) => path.contains(pattern.as_str()),
+            Self::Exact(pattern) => path == pattern,
+            Self::Glob(pattern) => glob(pattern, path),
+        }
+    }
+}
+
+/// match the whole text against the glob, backtracking to the last `*`
+pub(crate) fn glob(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // the position of the last `*` and of the text it matched up to
+    let mut star = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
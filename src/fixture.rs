@@ -0,0 +1,69 @@
+use crate::settings::Setting;
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt,
+    sync::{Arc, RwLock},
+};
+
+/// the values provided by the setups of a group to its tests
+///
+/// every group has its own scope of fixtures, looking up a value
+/// falls back to the enclosing groups.
+pub(crate) struct Fixtures {
+    values: RwLock<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
+    parent: Option<Arc<Fixtures>>,
+}
+
+impl Fixtures {
+    pub(crate) fn root() -> Arc<Self> {
+        Arc::new(Self {
+            values: RwLock::new(HashMap::new()),
+            parent: None,
+        })
+    }
+
+    pub(crate) fn scope(parent: &Arc<Self>) -> Arc<Self> {
+        Arc::new(Self {
+            values: RwLock::new(HashMap::new()),
+            parent: Some(Arc::clone(parent)),
+        })
+    }
+
+    pub(crate) fn insert<T>(&self, value: T)
+    where
+        T: Any + Send + Sync,
+    {
+        self.values
+            .write()
+            .unwrap()
+            .insert(TypeId::of::<T>(), Arc::new(value));
+    }
+
+    pub(crate) fn get<T>(&self) -> Option<Arc<T>>
+    where
+        T: Any + Send + Sync,
+    {
+        let value = self.values.read().unwrap().get(&TypeId::of::<T>()).cloned();
+        match value {
+            Some(value) => Arc::downcast(value).ok(),
+            None => self.parent.as_ref()?.get(),
+        }
+    }
+
+    /// drop all the values of this scope
+    pub(crate) fn clear(&self) {
+        self.values.write().unwrap().clear();
+    }
+}
+
+impl fmt::Debug for Fixtures {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Fixtures")
+            .field("values", &self.values.read().unwrap().len())
+            .field("parent", &self.parent)
+            .finish()
+    }
+}
+
+impl Setting for Fixtures {}
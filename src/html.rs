@@ -0,0 +1,354 @@
+use crate::{
+    reporter::{self, Collector, Summary},
+    Ordering, Outcome, Report, Reporter, TestName, TestResult, TestedResult,
+};
+use std::{
+    borrow::Cow,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+    time::Duration,
+};
+
+/// the statuses of the results, in the order of the filters
+const STATUSES: [&str; 5] = ["passed", "flaky", "failed", "timeout", "skipped"];
+
+const STYLE: &str = r#"
+body { font-family: sans-serif; margin: 2em; color: #222; }
+h1 { font-size: 1.4em; }
+#filters label { margin-right: 1em; }
+#filters button { margin-left: 1em; }
+details { margin-left: 1.2em; }
+summary { cursor: pointer; padding: 2px 0; }
+summary .counts, .duration { color: #777; font-size: 0.9em; margin-left: 0.5em; }
+ul { list-style: none; margin: 0; padding-left: 1.2em; }
+li { padding: 2px 0; }
+.status { display: inline-block; min-width: 5.5em; font-weight: bold; }
+.kind { color: #777; }
+pre { background: #f5f5f5; border-left: 3px solid #ccc; margin: 4px 0 4px 5.5em; padding: 4px 8px; white-space: pre-wrap; }
+.passed > .status { color: #2e7d32; }
+.flaky > .status { color: #b8860b; }
+.failed > .status { color: #c62828; }
+.timeout > .status { color: #8e24aa; }
+.skipped > .status { color: #9e9e9e; }
+details.failed > summary .name { color: #c62828; }
+"#;
+
+const SCRIPT: &str = r#"
+document.querySelectorAll('#filters input').forEach(function (input) {
+  input.addEventListener('change', function () {
+    document.body.classList.toggle('hide-' + input.dataset.status, !input.checked);
+  });
+});
+document.querySelectorAll('#filters button').forEach(function (button) {
+  button.addEventListener('click', function () {
+    var open = button.dataset.open === 'true';
+    document.querySelectorAll('main details').forEach(function (group) {
+      group.open = open;
+    });
+  });
+});
+"#;
+
+/// writes the reports of the runs as a self-contained HTML page in a
+/// file, see [`Report::write_html`]
+///
+/// The file is written again at the end of every run with the reports of
+/// all the runs so far, so the `#[scaffold]` functions of a test binary
+/// sharing the reporter end up in the same page.
+#[derive(Debug)]
+pub struct Html {
+    path: PathBuf,
+    collector: Collector,
+}
+
+impl Html {
+    /// write the reports in the file at the given path, its directory is
+    /// created if it does not exist
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self {
+            path: path.into(),
+            collector: Collector::default(),
+        }
+    }
+}
+
+impl Reporter for Html {
+    fn group_start(&mut self, name: &TestName, ordering: &Ordering) -> io::Result<()> {
+        self.collector.push(TestedResult::GroupStart {
+            name: name.clone(),
+            ordering: *ordering,
+        });
+        Ok(())
+    }
+
+    fn group_end(&mut self, name: &TestName, ordering: &Ordering) -> io::Result<()> {
+        self.collector.push(TestedResult::GroupEnd {
+            name: name.clone(),
+            ordering: *ordering,
+        });
+        Ok(())
+    }
+
+    fn test_result(&mut self, result: &TestedResult) -> io::Result<()> {
+        self.collector.push(result.clone());
+        Ok(())
+    }
+
+    fn run_end(&mut self, _: &Summary) -> io::Result<()> {
+        let mut output = BufWriter::new(reporter::create(&self.path)?);
+        write_html(self.collector.complete(), &mut output)?;
+        output.flush()
+    }
+}
+
+impl Report {
+    /// write the report as a self-contained HTML page, to read offline
+    ///
+    /// The groups are collapsible, only the ones with failures are open.
+    /// The results are coloured by status (passed, flaky, failed, timeout
+    /// or skipped) and can be filtered by status. Every result shows its
+    /// duration, its details and its output.
+    pub fn write_html<O: Write>(&self, output: &mut O) -> io::Result<()> {
+        write_html(std::slice::from_ref(self), output)
+    }
+
+    /// the report as a self-contained HTML page, see [`Report::write_html`]
+    pub fn to_html(&self) -> String {
+        let mut output = Vec::new();
+        self.write_html(&mut output)
+            .expect("writing in memory does not fail");
+        String::from_utf8(output).expect("the page is valid UTF-8")
+    }
+}
+
+/// the number of results of each status, in the order of [`STATUSES`]
+#[derive(Default)]
+struct Counts {
+    statuses: [usize; STATUSES.len()],
+    duration: Duration,
+}
+
+impl Counts {
+    fn of(report: &Report) -> Self {
+        let mut counts = Self::default();
+        counts.add(report);
+        counts
+    }
+
+    fn count(&self, status: &str) -> usize {
+        self.statuses[index(status)]
+    }
+
+    fn add(&mut self, report: &Report) {
+        match report {
+            Report::Grouped { reports } | Report::Group { reports, .. } => {
+                for report in reports {
+                    self.add(report);
+                }
+            }
+            Report::Test { result, .. }
+            | Report::Setup { result, .. }
+            | Report::Teardown { result, .. } => {
+                self.statuses[index(status(result))] += 1;
+                self.duration += result.duration;
+            }
+        }
+    }
+
+    fn failed(&self) -> bool {
+        self.count("failed") > 0 || self.count("timeout") > 0
+    }
+
+    /// the counts of the statuses present, like `3 passed, 1 failed`
+    fn describe(&self) -> String {
+        STATUSES
+            .iter()
+            .zip(&self.statuses)
+            .filter(|(_, count)| **count > 0)
+            .map(|(status, count)| format!("{} {}", count, status))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+fn write_html<O: Write>(reports: &[Report], output: &mut O) -> io::Result<()> {
+    let mut counts = Counts::default();
+    for report in reports {
+        counts.add(report);
+    }
+
+    writeln!(output, "<!DOCTYPE html>")?;
+    writeln!(output, r#"<html lang="en">"#)?;
+    writeln!(output, "<head>")?;
+    writeln!(output, r#"<meta charset="utf-8">"#)?;
+    writeln!(output, "<title>Test report</title>")?;
+    write!(output, "<style>{}", STYLE)?;
+    for status in STATUSES.iter() {
+        writeln!(output, "body.hide-{0} li.{0} {{ display: none; }}", status)?;
+    }
+    writeln!(output, "</style>")?;
+    writeln!(output, "</head>")?;
+    writeln!(output, "<body>")?;
+    writeln!(output, "<header>")?;
+    writeln!(output, "<h1>Test report</h1>")?;
+    writeln!(
+        output,
+        r#"<p class="summary">{} <span class="duration">{}</span></p>"#,
+        escape(&counts.describe()),
+        duration(counts.duration)
+    )?;
+    writeln!(output, r#"<nav id="filters">"#)?;
+    for (status, count) in STATUSES.iter().zip(&counts.statuses) {
+        writeln!(
+            output,
+            r#"<label class="{0}"><input type="checkbox" data-status="{0}" checked> <span class="status">{0}</span> ({1})</label>"#,
+            status, count
+        )?;
+    }
+    writeln!(output, r#"<button data-open="true">expand all</button>"#)?;
+    writeln!(output, r#"<button data-open="false">collapse all</button>"#)?;
+    writeln!(output, "</nav>")?;
+    writeln!(output, "</header>")?;
+
+    writeln!(output, "<main>")?;
+    writeln!(output, "<ul>")?;
+    for report in reports {
+        write_report(report, output)?;
+    }
+    writeln!(output, "</ul>")?;
+    writeln!(output, "</main>")?;
+    writeln!(output, "<script>{}</script>", SCRIPT)?;
+    writeln!(output, "</body>")?;
+    writeln!(output, "</html>")
+}
+
+fn write_report<O: Write>(report: &Report, output: &mut O) -> io::Result<()> {
+    match report {
+        Report::Grouped { reports } => {
+            for report in reports {
+                write_report(report, output)?;
+            }
+            Ok(())
+        }
+        Report::Group {
+            name,
+            ordering,
+            reports,
+        } => {
+            let counts = Counts::of(report);
+            writeln!(output, "<li>")?;
+            writeln!(
+                output,
+                r#"<details class="{}"{}>"#,
+                if counts.failed() { "failed" } else { "passed" },
+                if counts.failed() { " open" } else { "" },
+            )?;
+            write!(
+                output,
+                r#"<summary><span class="name">{}</span> <span class="counts">{}</span> <span class="duration">{}</span>"#,
+                escape(name),
+                escape(&counts.describe()),
+                duration(counts.duration),
+            )?;
+            if let Ordering::Shuffled { seed: Some(seed) } = ordering {
+                write!(
+                    output,
+                    r#" <span class="duration">shuffled with seed {}</span>"#,
+                    seed
+                )?;
+            }
+            writeln!(output, "</summary>")?;
+            writeln!(output, "<ul>")?;
+            for report in reports {
+                write_report(report, output)?;
+            }
+            writeln!(output, "</ul>")?;
+            writeln!(output, "</details>")?;
+            writeln!(output, "</li>")
+        }
+        Report::Test { name, result, tags } => {
+            let tags = if tags.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", tags.join(", "))
+            };
+            write_result("", &format!("{}{}", name, tags), result, output)
+        }
+        Report::Setup { name, result } => write_result("setup ", name, result, output),
+        Report::Teardown { name, result } => write_result("teardown ", name, result, output),
+    }
+}
+
+fn write_result<O: Write>(
+    kind: &str,
+    name: &str,
+    result: &TestResult,
+    output: &mut O,
+) -> io::Result<()> {
+    write!(
+        output,
+        r#"<li class="{}"><span class="status">{}</span> <span class="kind">{}</span><span class="name">{}</span> <span class="duration">{}</span>"#,
+        status(result),
+        escape(&result.short),
+        kind,
+        escape(name),
+        duration(result.duration),
+    )?;
+    if !result.details.is_empty() {
+        write!(
+            output,
+            r#"<pre class="details">{}</pre>"#,
+            escape(result.details.trim_end())
+        )?;
+    }
+    if !result.output.is_empty() {
+        write!(
+            output,
+            r#"<pre class="output">{}</pre>"#,
+            escape(result.output.trim_end())
+        )?;
+    }
+    writeln!(output, "</li>")
+}
+
+/// the status of the result, one of [`STATUSES`]
+fn status(result: &TestResult) -> &'static str {
+    match &result.outcome {
+        Outcome::Success => "passed",
+        Outcome::Flaky => "flaky",
+        Outcome::Skipped | Outcome::Ignored => "skipped",
+        _ if result.is_timeout() || result.is_leaked() => "timeout",
+        Outcome::Failure { .. } => "failed",
+    }
+}
+
+/// the position of the status in [`STATUSES`]
+fn index(status: &str) -> usize {
+    STATUSES
+        .iter()
+        .position(|s| *s == status)
+        .expect("the statuses are listed")
+}
+
+fn duration(duration: Duration) -> String {
+    format!("{:.2?}", duration)
+}
+
+fn escape(text: &str) -> Cow<'_, str> {
+    if !text.contains(['&', '<', '>', '"', '\'']) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut escaped = String::with_capacity(text.len() + 16);
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
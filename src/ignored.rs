@@ -0,0 +1,78 @@
+use crate::{TestName, TestResult};
+use std::env;
+
+/// environment variable selecting whether the ignored tests run,
+/// see [`RunIgnored::from_env`]
+pub(crate) const IGNORED_ENV: &str = "SCAFFOLDING_IGNORED";
+
+/// why a test is not executed, see [`TestTree::ignore`] and
+/// [`TestTree::todo`]
+///
+/// [`TestTree::ignore`]: crate::TestTree::ignore
+/// [`TestTree::todo`]: crate::TestTree::todo
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Ignored {
+    /// the test is ignored for the given reason, it is executed anyway
+    /// when running the ignored tests (see [`RunIgnored`])
+    Reason(TestName),
+    /// the test is not implemented yet, it is never executed
+    Todo,
+}
+
+/// whether the tests marked as ignored are executed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RunIgnored {
+    /// the ignored tests are listed but not executed
+    #[default]
+    No,
+    /// the ignored tests are executed with the other tests
+    Include,
+    /// only the ignored tests are executed
+    Only,
+}
+
+impl Ignored {
+    /// the result reported for the test instead of executing it
+    pub(crate) fn result(&self) -> TestResult {
+        match self {
+            Self::Reason(reason) => TestResult::ignored(reason.clone()),
+            Self::Todo => TestResult::todo(),
+        }
+    }
+}
+
+impl RunIgnored {
+    /// the mode given to the process with the `SCAFFOLDING_IGNORED`
+    /// environment variable, `include` or `only`
+    ///
+    /// The arguments of the test harness (`--include-ignored` and
+    /// `--ignored`) are not used: with `--ignored` the test harness only
+    /// runs the `#[ignore]` functions, never the `#[scaffold]` ones.
+    ///
+    /// # panics
+    ///
+    /// if the environment variable is neither empty, `include` nor `only`
+    pub fn from_env() -> Self {
+        let mode = match env::var(IGNORED_ENV) {
+            Ok(mode) => mode,
+            Err(_) => return Self::No,
+        };
+        match mode.trim() {
+            "" => Self::No,
+            "include" => Self::Include,
+            "only" => Self::Only,
+            _ => panic!(
+                "invalid {}: {:?}, expected one of: only, include",
+                IGNORED_ENV, mode
+            ),
+        }
+    }
+
+    /// returns `true` if a test ignored this way is executed
+    pub(crate) fn runs(self, ignored: &Ignored) -> bool {
+        match ignored {
+            Ignored::Reason(_) => self != Self::No,
+            Ignored::Todo => false,
+        }
+    }
+}
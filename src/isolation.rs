@@ -0,0 +1,312 @@
+use crate::{
+    executor::captured,
+    settings::{Capture, GracePeriod, Timeout},
+    FailureReason, Outcome, Settings, TestResult,
+};
+use std::{
+    borrow::Cow,
+    env,
+    ffi::OsString,
+    io::{self, Read, Write},
+    process::{self, Child, Command, ExitStatus, Stdio},
+    str::FromStr,
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+/// the hidden argument selecting the test to run in the child process
+///
+/// it is passed as a test filter so it does not upset the command line
+/// parser of the test harness.
+pub(crate) const ARGUMENT: &str = "__scaffolding_isolated=";
+/// separates the output of the child process from the result of its test
+const MARKER: &str = "\n\u{1}scaffolding-result\u{1}\n";
+/// how long the child process has to start and report its result on top
+/// of the [`Timeout`] and the [`GracePeriod`] of its test
+const MARGIN: Duration = Duration::from_secs(1);
+const POLL_INTERVAL: Duration = Duration::from_millis(2);
+
+/// how to run the current test binary again
+#[derive(Debug, Clone)]
+pub(crate) struct Harness {
+    arguments: Vec<OsString>,
+}
+
+/// the path of the test to run, if this process is running an isolated test
+pub(crate) fn selected() -> Option<String> {
+    env::args().find_map(|argument| argument.strip_prefix(ARGUMENT).map(str::to_owned))
+}
+
+/// report the result of the isolated test to the parent process and exit
+pub(crate) fn report(result: &TestResult) -> ! {
+    let mut stdout = io::stdout();
+    let _ = stdout
+        .write_all(encode(result).as_bytes())
+        .and_then(|()| stdout.flush());
+    process::exit(0)
+}
+
+impl Harness {
+    /// the arguments to run again the test being executed by this thread
+    ///
+    /// The test harness names the threads of the tests after them, the
+    /// test is then selected with an exact filter. Otherwise this is a
+    /// custom harness and it is given the same arguments again.
+    pub(crate) fn current() -> Self {
+        let arguments = match thread::current().name() {
+            Some(name) if name != "main" => vec![
+                name.into(),
+                "--exact".into(),
+                "--nocapture".into(),
+                "--test-threads=1".into(),
+                "--quiet".into(),
+            ],
+            _ => env::args_os()
+                .skip(1)
+                .filter(|argument| !argument.to_string_lossy().starts_with(ARGUMENT))
+                .collect(),
+        };
+
+        Self { arguments }
+    }
+
+    /// run the test at the given path in a child process
+    pub(crate) fn run(&self, path: &str, seed: u64, settings: &Settings) -> TestResult {
+        let instant = Instant::now();
+        let result = self.run_(path, seed, settings).unwrap_or_else(|error| {
+            TestResult::failed(format!("cannot run the test process: {}", error))
+        });
+
+        TestResult {
+            duration: instant.elapsed(),
+            ..result
+        }
+    }
+
+    fn run_(&self, path: &str, seed: u64, settings: &Settings) -> io::Result<TestResult> {
+        let mut child = Command::new(env::current_exe()?)
+            .args(&self.arguments)
+            .arg(format!("{}{}", ARGUMENT, path))
+            .env(crate::rng::SEED_ENV, seed.to_string())
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let stdout = drain(child.stdout.take());
+        let stderr = drain(child.stderr.take());
+
+        let timeout = settings.get_or_default::<Timeout>().0;
+        let grace_period = settings.get_or_default::<GracePeriod>().0;
+        let status = wait(&mut child, timeout + grace_period + MARGIN)?;
+
+        let stdout = stdout.join().unwrap_or_default();
+        let stderr = stderr.join().unwrap_or_default();
+        let stdout = String::from_utf8_lossy(&stdout);
+        let stderr = String::from_utf8_lossy(&stderr);
+        let (output, result) = match stdout.rfind(MARKER) {
+            Some(marker) => (&stdout[..marker], decode(&stdout[marker + MARKER.len()..])),
+            None => (stdout.as_ref(), None),
+        };
+        // what the test harness prints before running the test
+        let output = output
+            .trim_start_matches('\n')
+            .strip_prefix("running 1 test\n")
+            .unwrap_or(output);
+
+        let result = match (status, result) {
+            (None, _) => TestResult::timedout(format!(
+                "Test timedout, allocated duration was {:.2?}, its process was killed",
+                timeout
+            )),
+            (Some(_), Some(result)) if result.is_leaked() => TestResult {
+                details: format!("{}, its process was terminated", result.details).into(),
+                ..TestResult::timedout("")
+            },
+            (Some(_), Some(result)) => result,
+            (Some(status), None) => crashed(status, &stderr),
+        };
+
+        let mode = *settings.get_or_default::<Capture>();
+        if mode == Capture::Off {
+            let _ = io::stdout().write_all(output.as_bytes());
+            let _ = io::stderr().write_all(stderr.as_bytes());
+            Ok(result)
+        } else {
+            Ok(captured(result, format!("{}{}", output, stderr), mode))
+        }
+    }
+}
+
+/// read everything the child process writes in the given pipe
+fn drain<R>(pipe: Option<R>) -> JoinHandle<Vec<u8>>
+where
+    R: Read + Send + 'static,
+{
+    thread::spawn(move || {
+        let mut output = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut output);
+        }
+        output
+    })
+}
+
+/// wait for the child to exit, killing it if it is still running after
+/// the given duration
+fn wait(child: &mut Child, duration: Duration) -> io::Result<Option<ExitStatus>> {
+    let instant = Instant::now();
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if instant.elapsed() >= duration {
+            child.kill()?;
+            child.wait()?;
+            return Ok(None);
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// the result of a test whose process exited without reporting it
+///
+/// the standard error is where the runtime explains the crash
+fn crashed(status: ExitStatus, stderr: &str) -> TestResult {
+    let (reason, short, details) = if stderr.contains("has overflowed its stack") {
+        (
+            FailureReason::StackOverflow,
+            "Overflow",
+            "the test overflowed its stack".to_owned(),
+        )
+    } else if let Some(signal) = signal(&status) {
+        if signal == SIGABRT {
+            (
+                FailureReason::Aborted,
+                "Aborted",
+                "the test aborted its process".to_owned(),
+            )
+        } else {
+            (
+                FailureReason::Crashed { signal },
+                "Crashed",
+                format!(
+                    "the process of the test was killed by the signal {}",
+                    signal
+                ),
+            )
+        }
+    } else {
+        let code = status.code().unwrap_or_default();
+        (
+            FailureReason::Exited { code },
+            "Exited",
+            format!("the test exited its process with the code {}", code),
+        )
+    };
+
+    TestResult {
+        outcome: Outcome::Failure { reason },
+        short: Cow::Borrowed(short),
+        details: details.into(),
+        output: Cow::Borrowed(""),
+        duration: Duration::default(),
+    }
+}
+
+const SIGABRT: i32 = 6;
+
+#[cfg(unix)]
+fn signal(status: &ExitStatus) -> Option<i32> {
+    std::os::unix::process::ExitStatusExt::signal(status)
+}
+
+#[cfg(not(unix))]
+fn signal(_: &ExitStatus) -> Option<i32> {
+    None
+}
+
+fn encode(result: &TestResult) -> String {
+    let outcome = match &result.outcome {
+        Outcome::Success => "success".to_owned(),
+        Outcome::Flaky => "flaky".to_owned(),
+        Outcome::Skipped => "skipped".to_owned(),
+        Outcome::Ignored => "ignored".to_owned(),
+        Outcome::Failure { reason } => match reason {
+            FailureReason::Failed => "failed".to_owned(),
+            FailureReason::Panicked => "panicked".to_owned(),
+            FailureReason::Timedout => "timedout".to_owned(),
+            FailureReason::Leaked => "leaked".to_owned(),
+            FailureReason::Aborted => "aborted".to_owned(),
+            FailureReason::StackOverflow => "stack-overflow".to_owned(),
+            FailureReason::Crashed { signal } => format!("crashed {}", signal),
+            FailureReason::Exited { code } => format!("exited {}", code),
+            FailureReason::UnexpectedPass => "unexpected-pass".to_owned(),
+        },
+    };
+
+    format!(
+        "{marker}{outcome}\n{duration}\n{short_len}\n{short}{details_len}\n{details}",
+        marker = MARKER,
+        outcome = outcome,
+        duration = result.duration.as_nanos(),
+        short_len = result.short.len(),
+        short = result.short,
+        details_len = result.details.len(),
+        details = result.details,
+    )
+}
+
+/// decode the result written after the [`MARKER`]
+fn decode(output: &str) -> Option<TestResult> {
+    fn line<'a>(input: &mut &'a str) -> Option<&'a str> {
+        let (line, rest) = input.split_at(input.find('\n')?);
+        *input = &rest[1..];
+        Some(line)
+    }
+    fn sized(input: &mut &str) -> Option<String> {
+        let len = usize::from_str(line(input)?).ok()?;
+        let value = input.get(..len)?.to_owned();
+        *input = &input[len..];
+        Some(value)
+    }
+
+    let mut input = output;
+    let outcome = line(&mut input)?;
+    let duration = Duration::from_nanos(u64::from_str(line(&mut input)?).ok()?);
+    let short = sized(&mut input)?;
+    let details = sized(&mut input)?;
+
+    let mut words = outcome.split(' ');
+    let outcome = match (words.next()?, words.next()) {
+        ("success", None) => Outcome::Success,
+        ("flaky", None) => Outcome::Flaky,
+        ("skipped", None) => Outcome::Skipped,
+        ("ignored", None) => Outcome::Ignored,
+        (reason, value) => Outcome::Failure {
+            reason: match (reason, value) {
+                ("failed", None) => FailureReason::Failed,
+                ("panicked", None) => FailureReason::Panicked,
+                ("timedout", None) => FailureReason::Timedout,
+                ("leaked", None) => FailureReason::Leaked,
+                ("aborted", None) => FailureReason::Aborted,
+                ("stack-overflow", None) => FailureReason::StackOverflow,
+                ("unexpected-pass", None) => FailureReason::UnexpectedPass,
+                ("crashed", Some(signal)) => FailureReason::Crashed {
+                    signal: signal.parse().ok()?,
+                },
+                ("exited", Some(code)) => FailureReason::Exited {
+                    code: code.parse().ok()?,
+                },
+                _ => return None,
+            },
+        },
+    };
+
+    Some(TestResult {
+        outcome,
+        short: short.into(),
+        details: details.into(),
+        output: Cow::Borrowed(""),
+        duration,
+    })
+}
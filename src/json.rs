@@ -0,0 +1,221 @@
+use crate::{
+    reporter::Summary,
+    test_tree::{self, PATH_SEPARATOR},
+    Ordering, Outcome, Plan, Reporter, Terminal, TestName, TestResult, TestedResult,
+};
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+};
+
+/// reports the run as JSON lines, in the shape of the (unstable) JSON
+/// format of the test harness (`cargo test -- --format json`)
+///
+/// Every event is one JSON object on its own line:
+///
+/// ```text
+/// { "type": "suite", "event": "started", "test_count": 2 }
+/// { "type": "test", "event": "started", "name": "root::passes" }
+/// { "type": "test", "name": "root::passes", "event": "ok", "exec_time": 0.001, ... }
+/// { "type": "test", "event": "started", "name": "root::fails" }
+/// { "type": "test", "name": "root::fails", "event": "failed", "exec_time": 0.001, "stdout": "...", ... }
+/// { "type": "suite", "event": "failed", "passed": 1, "failed": 1, "ignored": 0, "measured": 0, "filtered_out": 0, "exec_time": 0.002 }
+/// ```
+///
+/// The name of a test is its path. The setups and teardowns are reported
+/// as tests named `setup <name>` and `teardown <name>` in their group,
+/// the skipped tests as ignored ones: they are counted in the `ignored`
+/// of the suite. Its `filtered_out` counts the tests left out of the run
+/// (see [`Plan::filtered_out`]). On top of the fields of the test
+/// harness, the results have a `scaffolding` object giving the `group`
+/// (the path of the group of the test), the `kind` (`test`, `setup` or
+/// `teardown`), the `short` description of the result and the `reason` of
+/// the failures (like `panicked` or `timedout`).
+pub struct Json<O> {
+    output: O,
+    /// the path of the group being reported
+    groups: Vec<TestName>,
+    passed: usize,
+    failed: usize,
+    ignored: usize,
+    filtered_out: usize,
+}
+
+impl Json<Terminal> {
+    pub fn stdout() -> Self {
+        Self::new_with(Terminal::default())
+    }
+}
+
+impl<O> Json<O>
+where
+    O: Write,
+{
+    pub fn new_with(output: O) -> Self {
+        Self {
+            output,
+            groups: Vec::new(),
+            passed: 0,
+            failed: 0,
+            ignored: 0,
+            filtered_out: 0,
+        }
+    }
+
+    fn group(&self) -> String {
+        self.groups.join(PATH_SEPARATOR)
+    }
+
+    fn path(&self, name: &str) -> String {
+        test_tree::join(&self.group(), name)
+    }
+
+    fn started(&mut self, name: &str) -> io::Result<()> {
+        writeln!(
+            self.output,
+            r#"{{ "type": "test", "event": "started", "name": {} }}"#,
+            string(&self.path(name))
+        )?;
+        self.output.flush()
+    }
+
+    fn result(&mut self, name: &str, kind: &str, result: &TestResult) -> io::Result<()> {
+        let event = match &result.outcome {
+            Outcome::Success | Outcome::Flaky => {
+                self.passed += 1;
+                "ok"
+            }
+            Outcome::Skipped | Outcome::Ignored => {
+                self.ignored += 1;
+                "ignored"
+            }
+            Outcome::Failure { .. } => {
+                self.failed += 1;
+                "failed"
+            }
+        };
+
+        let mut line = format!(
+            r#"{{ "type": "test", "name": {}, "event": "{}", "exec_time": {}"#,
+            string(&self.path(name)),
+            event,
+            result.duration.as_secs_f64(),
+        );
+        match &result.outcome {
+            Outcome::Failure { .. } => {
+                let stdout = [result.output.trim_end(), result.details.trim_end()]
+                    .iter()
+                    .filter(|text| !text.is_empty())
+                    .copied()
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                let _ = write!(line, r#", "stdout": {}"#, string(&stdout));
+            }
+            _ if !result.details.is_empty() => {
+                let _ = write!(line, r#", "message": {}"#, string(&result.details));
+            }
+            _ => (),
+        }
+        let _ = write!(
+            line,
+            r#", "scaffolding": {{ "group": {}, "kind": "{}", "short": {}"#,
+            string(&self.group()),
+            kind,
+            string(&result.short),
+        );
+        if let Outcome::Failure { reason } = &result.outcome {
+            let _ = write!(line, r#", "reason": "{}""#, reason.name());
+        }
+        line.push_str(" } }");
+
+        writeln!(self.output, "{}", line)?;
+        self.output.flush()
+    }
+}
+
+impl<O: Write> Reporter for Json<O> {
+    fn run_start(&mut self) -> io::Result<()> {
+        self.groups.clear();
+        self.passed = 0;
+        self.failed = 0;
+        self.ignored = 0;
+        self.filtered_out = 0;
+        Ok(())
+    }
+
+    fn plan(&mut self, plan: &Plan) -> io::Result<()> {
+        self.filtered_out = plan.filtered_out();
+        writeln!(
+            self.output,
+            r#"{{ "type": "suite", "event": "started", "test_count": {} }}"#,
+            plan.tests()
+        )?;
+        self.output.flush()
+    }
+
+    fn group_start(&mut self, name: &TestName, _: &Ordering) -> io::Result<()> {
+        self.groups.push(name.clone());
+        Ok(())
+    }
+
+    fn group_end(&mut self, _: &TestName, _: &Ordering) -> io::Result<()> {
+        self.groups.pop();
+        Ok(())
+    }
+
+    fn test_start(&mut self, name: &TestName) -> io::Result<()> {
+        self.started(name)
+    }
+
+    fn test_result(&mut self, result: &TestedResult) -> io::Result<()> {
+        match result {
+            TestedResult::Single { name, result, .. } => self.result(name, "test", result),
+            TestedResult::Setup { name, result } => {
+                let name = format!("setup {}", name);
+                self.started(&name)?;
+                self.result(&name, "setup", result)
+            }
+            TestedResult::Teardown { name, result } => {
+                let name = format!("teardown {}", name);
+                self.started(&name)?;
+                self.result(&name, "teardown", result)
+            }
+            TestedResult::GroupStart { .. } | TestedResult::GroupEnd { .. } => Ok(()),
+        }
+    }
+
+    fn run_end(&mut self, summary: &Summary) -> io::Result<()> {
+        writeln!(
+            self.output,
+            r#"{{ "type": "suite", "event": "{}", "passed": {}, "failed": {}, "ignored": {}, "measured": 0, "filtered_out": {}, "exec_time": {} }}"#,
+            if self.failed == 0 { "ok" } else { "failed" },
+            self.passed,
+            self.failed,
+            self.ignored,
+            self.filtered_out,
+            summary.duration.as_secs_f64(),
+        )?;
+        self.output.flush()
+    }
+}
+
+/// the text as a JSON string
+fn string(text: &str) -> String {
+    let mut json = String::with_capacity(text.len() + 2);
+    json.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if u32::from(c) < 0x20 => {
+                let _ = write!(json, "\\u{:04x}", u32::from(c));
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
@@ -0,0 +1,272 @@
+use crate::{
+    reporter::{self, Collector, Summary},
+    Ordering, Outcome, Report, Reporter, TestName, TestResult, TestedResult,
+};
+use std::{
+    borrow::Cow,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+    time::Duration,
+};
+
+/// the name of the suite of the tests outside of any group
+const DEFAULT_SUITE: &str = "tests";
+
+/// writes the reports of the runs as JUnit XML in a file, see
+/// [`Report::write_junit`]
+///
+/// The file is written again at the end of every run with the reports of
+/// all the runs so far, so the `#[scaffold]` functions of a test binary
+/// sharing the reporter end up in the same file.
+#[derive(Debug)]
+pub struct JUnit {
+    path: PathBuf,
+    collector: Collector,
+}
+
+impl JUnit {
+    /// write the reports in the file at the given path, its directory is
+    /// created if it does not exist
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self {
+            path: path.into(),
+            collector: Collector::default(),
+        }
+    }
+}
+
+impl Reporter for JUnit {
+    fn group_start(&mut self, name: &TestName, ordering: &Ordering) -> io::Result<()> {
+        self.collector.push(TestedResult::GroupStart {
+            name: name.clone(),
+            ordering: *ordering,
+        });
+        Ok(())
+    }
+
+    fn group_end(&mut self, name: &TestName, ordering: &Ordering) -> io::Result<()> {
+        self.collector.push(TestedResult::GroupEnd {
+            name: name.clone(),
+            ordering: *ordering,
+        });
+        Ok(())
+    }
+
+    fn test_result(&mut self, result: &TestedResult) -> io::Result<()> {
+        self.collector.push(result.clone());
+        Ok(())
+    }
+
+    fn run_end(&mut self, _: &Summary) -> io::Result<()> {
+        let mut output = BufWriter::new(reporter::create(&self.path)?);
+        write_junit(self.collector.complete(), &mut output)?;
+        output.flush()
+    }
+}
+
+impl Report {
+    /// write the report as JUnit XML
+    ///
+    /// Every group is a `<testsuite>` of its tests, setups and teardowns,
+    /// named after the path of the group with the names joined with `.`
+    /// (like `root.nested`). The failures are `<failure>` elements whose
+    /// `type` is the [`FailureReason`] (`panicked`, `timedout`...), the
+    /// skipped and ignored tests are `<skipped>` elements. Both contain
+    /// the details of the result.
+    ///
+    /// [`FailureReason`]: crate::FailureReason
+    pub fn write_junit<O: Write>(&self, output: &mut O) -> io::Result<()> {
+        write_junit(std::slice::from_ref(self), output)
+    }
+}
+
+/// a `<testsuite>` and its `<testcase>`s
+struct Suite<'a> {
+    name: String,
+    cases: Vec<Case<'a>>,
+}
+
+struct Case<'a> {
+    name: Cow<'a, str>,
+    result: &'a TestResult,
+}
+
+fn write_junit<O: Write>(reports: &[Report], output: &mut O) -> io::Result<()> {
+    let mut suites = Vec::new();
+    let mut loose = Suite {
+        name: DEFAULT_SUITE.to_owned(),
+        cases: Vec::new(),
+    };
+    for report in reports {
+        collect(report, None, &mut loose, &mut suites);
+    }
+    if !loose.cases.is_empty() {
+        suites.insert(0, loose);
+    }
+
+    let cases = || suites.iter().flat_map(|suite| &suite.cases);
+    writeln!(output, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        output,
+        r#"<testsuites tests="{}" failures="{}" errors="0" skipped="{}" time="{}">"#,
+        cases().count(),
+        cases().filter(|case| case.result.is_failure()).count(),
+        cases().filter(|case| is_skipped(case.result)).count(),
+        seconds(cases().map(|case| case.result.duration).sum()),
+    )?;
+    for suite in &suites {
+        write_suite(suite, output)?;
+    }
+    writeln!(output, "</testsuites>")
+}
+
+/// gather the suites of the report, `suite` being the suite of the group
+/// the report belongs to
+fn collect<'a>(
+    report: &'a Report,
+    path: Option<&str>,
+    suite: &mut Suite<'a>,
+    suites: &mut Vec<Suite<'a>>,
+) {
+    match report {
+        Report::Grouped { reports } => {
+            for report in reports {
+                collect(report, path, suite, suites);
+            }
+        }
+        Report::Group { name, reports, .. } => {
+            let name = match path {
+                Some(path) => format!("{}.{}", path, name),
+                None => name.to_string(),
+            };
+            // the suite is listed before the suites of its sub groups
+            let index = suites.len();
+            let mut group = Suite {
+                name,
+                cases: Vec::new(),
+            };
+            let path = group.name.clone();
+            for report in reports {
+                collect(report, Some(&path), &mut group, suites);
+            }
+            if !group.cases.is_empty() {
+                suites.insert(index, group);
+            }
+        }
+        Report::Test { name, result, .. } => suite.cases.push(Case {
+            name: Cow::Borrowed(name),
+            result,
+        }),
+        Report::Setup { name, result } => suite.cases.push(Case {
+            name: Cow::Owned(format!("setup {}", name)),
+            result,
+        }),
+        Report::Teardown { name, result } => suite.cases.push(Case {
+            name: Cow::Owned(format!("teardown {}", name)),
+            result,
+        }),
+    }
+}
+
+fn write_suite<O: Write>(suite: &Suite<'_>, output: &mut O) -> io::Result<()> {
+    let cases = &suite.cases;
+    writeln!(
+        output,
+        r#"  <testsuite name="{}" tests="{}" failures="{}" errors="0" skipped="{}" time="{}">"#,
+        escape(&suite.name),
+        cases.len(),
+        cases.iter().filter(|case| case.result.is_failure()).count(),
+        cases.iter().filter(|case| is_skipped(case.result)).count(),
+        seconds(cases.iter().map(|case| case.result.duration).sum()),
+    )?;
+
+    for case in cases {
+        let result = case.result;
+        write!(
+            output,
+            r#"    <testcase name="{}" classname="{}" time="{}""#,
+            escape(&case.name),
+            escape(&suite.name),
+            seconds(result.duration),
+        )?;
+        let element = match &result.outcome {
+            Outcome::Success | Outcome::Flaky => None,
+            Outcome::Skipped | Outcome::Ignored => Some(("skipped", None)),
+            Outcome::Failure { reason } => Some(("failure", Some(reason.name()))),
+        };
+        if element.is_none() && result.output.is_empty() {
+            writeln!(output, "/>")?;
+            continue;
+        }
+        writeln!(output, ">")?;
+
+        if let Some((element, kind)) = element {
+            write!(
+                output,
+                r#"      <{} message="{}""#,
+                element,
+                escape(&result.short)
+            )?;
+            if let Some(kind) = kind {
+                write!(output, r#" type="{}""#, kind)?;
+            }
+            if result.details.is_empty() {
+                writeln!(output, "/>")?;
+            } else {
+                writeln!(
+                    output,
+                    ">{}</{}>",
+                    escape(result.details.trim_end()),
+                    element
+                )?;
+            }
+        }
+        if !result.output.is_empty() {
+            writeln!(
+                output,
+                "      <system-out>{}</system-out>",
+                escape(result.output.trim_end())
+            )?;
+        }
+        writeln!(output, "    </testcase>")?;
+    }
+
+    writeln!(output, "  </testsuite>")
+}
+
+fn is_skipped(result: &TestResult) -> bool {
+    result.is_skipped() || result.is_ignored()
+}
+
+fn seconds(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64())
+}
+
+/// escape the text for the attributes and the content of the elements
+///
+/// the control characters are not allowed in XML 1.0, even escaped, they
+/// are dropped (except for the tabulations and the new lines).
+fn escape(text: &str) -> Cow<'_, str> {
+    let needs_escaping = |c: char| {
+        matches!(c, '&' | '<' | '>' | '"' | '\'')
+            || (c.is_control() && !matches!(c, '\t' | '\n' | '\r'))
+    };
+    if !text.contains(needs_escaping) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut escaped = String::with_capacity(text.len() + 16);
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() => (),
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
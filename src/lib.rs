@@ -1,8 +1,30 @@
+mod cancellation;
+mod capture;
+mod context;
+mod dependency;
 mod executor;
+mod expect;
+mod filter;
+mod fixture;
+mod html;
+mod ignored;
+mod isolation;
+mod json;
+mod junit;
 mod ordering;
 mod outcome;
+mod panics;
+mod pool;
+mod repeat;
 mod report;
+mod reporter;
 mod result;
+mod retry;
+mod rng;
+mod runtime;
+mod shard;
+mod tags;
+mod tap;
 mod test;
 mod test_tree;
 
@@ -10,13 +32,27 @@ pub mod provider;
 pub mod settings;
 
 pub use self::{
+    cancellation::{CancellationToken, Cancelled},
+    capture::Terminal,
+    context::TestContext,
+    dependency::DependencyError,
     executor::{Executor, TestedResult},
+    filter::Filter,
+    html::Html,
+    ignored::{Ignored, RunIgnored},
+    json::Json,
+    junit::JUnit,
     ordering::{Ordering, OrderingCondition},
     outcome::{FailureReason, Outcome},
-    report::{Progress, Report},
+    report::{Progress, Report, TagSummary},
+    reporter::{FanOut, Plan, Quiet, Reporter, Summary},
     result::TestResult,
+    rng::Rng,
     settings::Settings,
-    test::IsTest,
+    shard::Shard,
+    tags::{TagExpression, TagExpressionError},
+    tap::Tap,
+    test::{IntoTest, IsTest},
     test_tree::TestTree,
 };
 pub use scaffolding_macro::scaffold;
@@ -29,8 +65,40 @@ use std::{
 pub type TestName = Cow<'static, str>;
 
 /// create a group of tests and unit tests
+///
+/// the group can be given tags, inherited by its tests (see
+/// [`TestTree::tag`]):
+///
+/// ```
+/// # use scaffolding::{group, single};
+/// let tests = group!(
+///     "database",
+///     tags = ["db", "slow"],
+///     [single!("connects", || true)]
+/// );
+/// # let _ = tests;
+/// ```
 #[macro_export]
 macro_rules! group {
+    (
+        $test_name:expr,
+        tags = [$($tag:expr),* $(,)?],
+        [
+            $($test:expr),+ $(,)?
+        ]
+    ) => {{
+        $crate::group!($test_name, [$($test),+])$(.tag($tag))*
+    }};
+    (
+        $test_name:expr,
+        $ordering:expr,
+        tags = [$($tag:expr),* $(,)?],
+        [
+            $($test:expr),+ $(,)?
+        ]
+    ) => {{
+        $crate::group!($test_name, $ordering, [$($test),+])$(.tag($tag))*
+    }};
     (
         $test_name:expr,
         $ordering:expr,
@@ -41,7 +109,10 @@ macro_rules! group {
         $crate::TestTree::Group {
             name: $crate::TestName::from($test_name),
             ordering: $ordering,
-            tests: ::std::vec![$($test),+]
+            tests: ::std::vec![$($test),+],
+            dependencies: ::std::vec::Vec::new(),
+            tags: ::std::vec::Vec::new(),
+            ignored: ::std::option::Option::None,
         }
     }};
     (
@@ -69,15 +140,68 @@ macro_rules! setting {
 
 /// create a single test case
 ///
+/// the test is either a type implementing [`IsTest`], a closure without
+/// argument or a closure taking a [`TestContext`]. It can be given tags
+/// like `single!("name", tags = ["slow"], || ...)`, see [`TestTree::tag`].
 #[macro_export]
 macro_rules! single {
+    ($test_name:expr, tags = [$($tag:expr),* $(,)?], $test:expr $(,)?) => {{
+        $crate::single!($test_name, $test)$(.tag($tag))*
+    }};
     ($test:expr $(,)?) => {{
         $crate::single!(::std::stringify!($test), $test)
     }};
     ($test_name:expr, $test:expr $(,)?) => {{
         $crate::TestTree::Single {
             name: $crate::TestName::from($test_name),
-            test: ::std::boxed::Box::new($test),
+            test: $crate::IntoTest::into_test($test),
+            dependencies: ::std::vec::Vec::new(),
+            tags: ::std::vec::Vec::new(),
+            ignored: ::std::option::Option::None,
+        }
+    }};
+}
+
+/// declare a test that is not implemented yet
+///
+/// the test is reported as todo but never executed, see [`TestTree::todo`].
+#[macro_export]
+macro_rules! todo_test {
+    ($test_name:expr $(,)?) => {{
+        $crate::single!($test_name, || ()).todo()
+    }};
+}
+
+/// create a setup for the enclosing group
+///
+/// the setup is executed before any other test of the group, regardless
+/// of where it is declared in the group.
+#[macro_export]
+macro_rules! setup {
+    ($test:expr $(,)?) => {{
+        $crate::setup!(::std::stringify!($test), $test)
+    }};
+    ($test_name:expr, $test:expr $(,)?) => {{
+        $crate::TestTree::Setup {
+            name: $crate::TestName::from($test_name),
+            test: $crate::IntoTest::into_test($test),
+        }
+    }};
+}
+
+/// create a teardown for the enclosing group
+///
+/// the teardown is executed after all the other tests of the group,
+/// even if they failed, panicked or timed out.
+#[macro_export]
+macro_rules! teardown {
+    ($test:expr $(,)?) => {{
+        $crate::teardown!(::std::stringify!($test), $test)
+    }};
+    ($test_name:expr, $test:expr $(,)?) => {{
+        $crate::TestTree::Teardown {
+            name: $crate::TestName::from($test_name),
+            test: $crate::IntoTest::into_test($test),
         }
     }};
 }
@@ -110,12 +234,22 @@ macro_rules! smoke {
 }
 
 static INIT_SCAFFOLDING: Once = Once::new();
-static mut SCAFFOLDING: MaybeUninit<Arc<Mutex<Progress<std::io::Stdout>>>> = MaybeUninit::uninit();
+static mut SCAFFOLDING: MaybeUninit<Arc<Mutex<Box<dyn Reporter + Send>>>> = MaybeUninit::uninit();
 
+/// execute the tests, reporting them with the reporters of
+/// `SCAFFOLDING_REPORTER` or on the standard output
+///
+/// The reporter is shared by all the calls so the reports of the
+/// functions running concurrently are not mixed together. The tests are
+/// executed as configured by the environment, see [`Executor::from_env`].
+///
+/// # panics
+///
+/// if any of the tests, setups or teardowns failed
 pub fn scaffold(tests: TestTree) {
     let scaffolding = unsafe {
         INIT_SCAFFOLDING.call_once(|| {
-            SCAFFOLDING = MaybeUninit::new(Arc::new(Mutex::new(Progress::stdout())));
+            SCAFFOLDING = MaybeUninit::new(Arc::new(Mutex::new(reporter::from_env_or_progress())));
         });
 
         // we called the `call_once` so it is guaranteed the scaffold is built already
@@ -123,38 +257,60 @@ pub fn scaffold(tests: TestTree) {
     };
 
     let mut scaffold = scaffolding.lock().unwrap();
-
-    let mut tested = 0usize;
-    let mut succeed = 0usize;
-    for result in Executor::new(tests) {
-        if let TestedResult::Single { result, .. } = &result {
-            if !result.is_skipped() {
-                tested += 1;
-                if result.is_success() {
-                    succeed += 1;
-                }
-            }
-        }
-
-        if let Err(error) = scaffold.handle(&result) {
-            std::mem::drop(scaffold);
-
-            panic!("Failed to report test's result: {error}", error = error)
-        }
-    }
+    let summary = reporter::run(Executor::from_env(tests), &mut *scaffold, std::mem::drop);
 
     // make sure to drop the scaffold before we
     // get any further with the handling (i.e. don't want to panic with the
     // mutex still locked)
     std::mem::drop(scaffold);
 
-    let fails = tested.saturating_sub(succeed);
-    if fails > 0 {
-        panic!(
+    conclude(summary)
+}
+
+/// execute the tests, reporting them with the given reporter
+///
+/// The tests are executed as configured by the environment, see
+/// [`Executor::from_env`].
+///
+/// # panics
+///
+/// if any of the tests, setups or teardowns failed
+pub fn scaffold_with<R>(tests: TestTree, reporter: &mut R)
+where
+    R: Reporter + ?Sized,
+{
+    conclude(reporter::run(
+        Executor::from_env(tests),
+        reporter,
+        std::mem::drop,
+    ))
+}
+
+fn conclude(summary: std::io::Result<Summary>) {
+    let summary = match summary {
+        Ok(summary) => summary,
+        Err(error) => panic!("Failed to report test's result: {error}", error = error),
+    };
+
+    if !summary.is_success() {
+        let mut message = format!(
             "failed {fails} out of {tests}",
-            fails = fails,
-            tests = tested
-        )
+            fails = summary.failed,
+            tests = summary.tested()
+        );
+        if summary.hooks_failed > 0 {
+            message.push_str(&format!(
+                ", {hooks} setup or teardown failed",
+                hooks = summary.hooks_failed
+            ));
+        }
+        if summary.leaked > 0 {
+            message.push_str(&format!(", {leaked} leaked", leaked = summary.leaked));
+        }
+        if summary.ignored > 0 {
+            message.push_str(&format!(", {ignored} ignored", ignored = summary.ignored));
+        }
+        panic!("{}", message)
     }
 }
 
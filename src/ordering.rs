@@ -24,8 +24,30 @@ pub enum Ordering {
     /// Any ordering as appropriate
     ///
     /// The test will be started in non deterministic order,
-    /// spawning in threads as appropriate
+    /// spawning in threads as appropriate. The number of tests
+    /// running at the same time is bounded by the [`Jobs`] setting.
+    ///
+    /// [`Jobs`]: crate::settings::Jobs
     Any,
     /// execute only in sequence, based on the ordering condition
+    ///
+    /// a nested group is considered as one test: the next test will
+    /// only start once all the tests of the group have completed.
     Sequence { on_condition: OrderingCondition },
+    /// execute in sequence but in a random order
+    ///
+    /// This is useful to find hidden dependencies between tests. The order
+    /// is drawn from the `seed`: the same seed always gives the same order.
+    /// If `None`, the seed of the run is used: it is randomly drawn once
+    /// for all the groups, or taken from the `SCAFFOLDING_SEED` environment
+    /// variable by [`Executor::from_env`]. The seed used is reported in the
+    /// [`TestedResult::GroupStart`] so it can be passed back in, each group
+    /// is shuffled with its own order derived from the seed and its path.
+    ///
+    /// The tests complete before the next one start, regardless of their
+    /// outcome (similar to [`OrderingCondition::Finish`]).
+    ///
+    /// [`TestedResult::GroupStart`]: crate::TestedResult::GroupStart
+    /// [`Executor::from_env`]: crate::Executor::from_env
+    Shuffled { seed: Option<u64> },
 }
@@ -2,7 +2,7 @@
 ///
 /// This is the opportunity to give more details about why
 /// a test may have failed.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum FailureReason {
     /// the test simply failed to execute.
     Failed,
@@ -10,24 +10,92 @@ pub enum FailureReason {
     Panicked,
     /// the test timedout
     Timedout,
+    /// the test timedout and was still running after its grace period
+    Leaked,
+    /// the test aborted its process, only reported for the tests running
+    /// in their own process (see [`Isolation`])
+    ///
+    /// [`Isolation`]: crate::settings::Isolation
+    Aborted,
+    /// the test overflowed its stack, only reported for the tests running
+    /// in their own process (see [`Isolation`])
+    ///
+    /// [`Isolation`]: crate::settings::Isolation
+    StackOverflow,
+    /// the process of the test was killed by the given signal (like a
+    /// segmentation fault), only reported for the tests running in their
+    /// own process (see [`Isolation`])
+    ///
+    /// [`Isolation`]: crate::settings::Isolation
+    Crashed { signal: i32 },
+    /// the test exited its process (with [`std::process::exit`]) before
+    /// completing, only reported for the tests running in their own
+    /// process (see [`Isolation`])
+    ///
+    /// [`Isolation`]: crate::settings::Isolation
+    Exited { code: i32 },
+    /// the test was expected to fail but succeeded (see
+    /// [`TestTree::should_fail`])
+    ///
+    /// [`TestTree::should_fail`]: crate::TestTree::should_fail
+    UnexpectedPass,
+}
+
+impl FailureReason {
+    /// a short name of the reason, like `panicked` or `timedout`
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Self::Failed => "failed",
+            Self::Panicked => "panicked",
+            Self::Timedout => "timedout",
+            Self::Leaked => "leaked",
+            Self::Aborted => "aborted",
+            Self::StackOverflow => "stack-overflow",
+            Self::Crashed { .. } => "crashed",
+            Self::Exited { .. } => "exited",
+            Self::UnexpectedPass => "unexpected-pass",
+        }
+    }
 }
 
 /// The test outcome
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Outcome {
     Success,
+    /// the test failed before succeeding on a later attempt (see [`Retry`]),
+    /// it still counts as a success
+    ///
+    /// [`Retry`]: crate::settings::Retry
+    Flaky,
     Skipped,
-    Failure { reason: FailureReason },
+    /// the test was not executed because it is marked as ignored or as
+    /// todo (see [`TestTree::ignore`] and [`TestTree::todo`])
+    ///
+    /// [`TestTree::ignore`]: crate::TestTree::ignore
+    /// [`TestTree::todo`]: crate::TestTree::todo
+    Ignored,
+    Failure {
+        reason: FailureReason,
+    },
 }
 
 impl Outcome {
     /// returns true if the [`Outcome`] is a success
-    /// (is [`Outcome::Success`]).
+    /// (is [`Outcome::Success`] or [`Outcome::Flaky`]).
     ///
-    /// This is similar to testing `matches!(outcome, Outcome::Success)`
+    /// This is similar to testing `matches!(outcome, Outcome::Success | Outcome::Flaky)`
     #[inline]
     pub fn is_success(&self) -> bool {
-        matches!(self, Self::Success)
+        matches!(self, Self::Success | Self::Flaky)
+    }
+
+    /// returns true if the [`Outcome`] is a success after some failed
+    /// attempts (is [`Outcome::Flaky`]).
+    ///
+    /// This is similar to testing `matches!(outcome, Outcome::Flaky)`
+    #[inline]
+    pub fn is_flaky(&self) -> bool {
+        matches!(self, Self::Flaky)
     }
 
     /// returns true if the [`Outcome`] is a skip
@@ -39,6 +107,15 @@ impl Outcome {
         matches!(self, Self::Skipped)
     }
 
+    /// returns true if the [`Outcome`] is an ignored test
+    /// (is [`Outcome::Ignored`]).
+    ///
+    /// This is similar to testing `matches!(outcome, Outcome::Ignored)`
+    #[inline]
+    pub fn is_ignored(&self) -> bool {
+        matches!(self, Self::Ignored)
+    }
+
     /// returns true if the [`Outcome`] is a failure
     /// (is [`Outcome::Failure`]).
     ///
@@ -61,4 +138,18 @@ impl Outcome {
             }
         )
     }
+
+    /// returns true if the [`Outcome`] is a leaked test
+    /// (is [`Outcome::Failure`]).
+    ///
+    /// This is similar to testing `matches!(outcome, Outcome::Failure { reason: FailureReason::Leaked })`
+    #[inline]
+    pub fn is_leaked(&self) -> bool {
+        matches!(
+            self,
+            Self::Failure {
+                reason: FailureReason::Leaked
+            }
+        )
+    }
 }
@@ -0,0 +1,144 @@
+use crate::settings::Backtrace;
+use std::{
+    any::Any,
+    backtrace,
+    cell::{Cell, RefCell},
+    fmt::Write as _,
+    panic::{self, AssertUnwindSafe, PanicHookInfo},
+    sync::Once,
+};
+
+thread_local! {
+    /// set while the thread is running a test, the panics are recorded
+    /// instead of printed
+    static CATCHING: Cell<Option<Backtrace>> = const { Cell::new(None) };
+    /// the description of the last panic of the thread
+    static LAST: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+static INSTALL: Once = Once::new();
+
+/// run the test, catching its panic
+///
+/// The panic hook is process-wide, it is installed once and only records
+/// the panics of the threads running a test (leaving the others to the
+/// previous hook) so the tests running at the same time do not interfere.
+/// On panic, the returned error describes the panic: its message, its
+/// location and, depending on the `backtrace` setting, its backtrace.
+pub(crate) fn catch<F, R>(backtrace: Backtrace, f: F) -> Result<R, String>
+where
+    F: FnOnce() -> R,
+{
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if let Some(backtrace) = CATCHING.with(Cell::get) {
+                let description = describe(info, backtrace);
+                LAST.with(|last| *last.borrow_mut() = Some(description));
+            } else {
+                previous(info)
+            }
+        }));
+    });
+
+    let previous = CATCHING.with(|catching| catching.replace(Some(backtrace)));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.with(|catching| catching.set(previous));
+
+    result.map_err(|payload| {
+        LAST.with(|last| last.borrow_mut().take())
+            .unwrap_or_else(|| format!("test panicked:\n{}", message(payload.as_ref())))
+    })
+}
+
+fn describe(info: &PanicHookInfo, backtrace: Backtrace) -> String {
+    let mut description = String::from("test panicked");
+    if let Some(location) = info.location() {
+        let _ = write!(description, " at {}", location);
+    }
+    let _ = write!(description, ":\n{}", message(info.payload()));
+
+    let trace = match backtrace {
+        Backtrace::Off => None,
+        Backtrace::Short => Some(short(&backtrace::Backtrace::force_capture().to_string())),
+        Backtrace::Full => Some(backtrace::Backtrace::force_capture().to_string()),
+    };
+    if let Some(trace) = trace {
+        let _ = write!(description, "\n\nstack backtrace:\n{}", trace.trim_end());
+    }
+
+    description
+}
+
+/// the message of the panic
+///
+/// the payload is a `&str` or a `String` when the panic was raised with
+/// a message, which is the case of `panic!` and of the assertions.
+fn message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_owned()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Box<dyn Any>".to_owned()
+    }
+}
+
+/// keep only the frames of the test in the backtrace
+///
+/// drops the frames of the panic machinery at the top of the backtrace
+/// and the frames of the runner at the bottom.
+fn short(backtrace: &str) -> String {
+    const PANIC: &[&str] = &[
+        "rust_begin_unwind",
+        "core::panicking::",
+        "std::panicking::begin_panic",
+        "std::panicking::panic_with_hook",
+    ];
+    const RUNNER: &[&str] = &[
+        "std::panicking::catch_unwind",
+        "std::panicking::try",
+        "std::panic::catch_unwind",
+        "scaffolding::test::",
+        "scaffolding::executor::",
+        "__rust_begin_short_backtrace",
+    ];
+
+    // every frame starts with its number, followed with its location
+    let mut frames: Vec<Vec<&str>> = Vec::new();
+    for line in backtrace.lines() {
+        let is_frame = line
+            .trim_start()
+            .split_once(':')
+            .is_some_and(|(number, _)| number.parse::<usize>().is_ok());
+        match frames.last_mut() {
+            Some(frame) if !is_frame => frame.push(line),
+            _ => frames.push(vec![line]),
+        }
+    }
+
+    let symbol = |frame: &[&str]| {
+        frame[0]
+            .trim_start()
+            .split_once(": ")
+            .map_or("", |(_, symbol)| symbol)
+            .to_owned()
+    };
+    let start = frames
+        .iter()
+        .rposition(|frame| PANIC.iter().any(|prefix| symbol(frame).contains(prefix)))
+        .map_or(0, |index| index + 1);
+    let end = frames[start..]
+        .iter()
+        .position(|frame| RUNNER.iter().any(|prefix| symbol(frame).contains(prefix)))
+        .map_or(frames.len(), |index| start + index);
+
+    frames[start..end]
+        .iter()
+        .flatten()
+        .fold(String::new(), |mut short, line| {
+            short.push_str(line);
+            short.push('\n');
+            short
+        })
+}
@@ -0,0 +1,62 @@
+use std::{
+    sync::{mpsc, Arc, Mutex},
+    thread,
+};
+
+type Task = Box<dyn FnOnce() + Send + 'static>;
+
+/// a bounded set of worker threads executing the tasks they are given
+///
+/// workers are spawned lazily, only when the pool is asked to grow.
+/// Dropping the pool lets the workers finish their current task and
+/// exit, it does not wait for them.
+pub(crate) struct WorkerPool {
+    sender: mpsc::Sender<Task>,
+    receiver: Arc<Mutex<mpsc::Receiver<Task>>>,
+    workers: usize,
+}
+
+impl WorkerPool {
+    pub(crate) fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            sender,
+            receiver: Arc::new(Mutex::new(receiver)),
+            workers: 0,
+        }
+    }
+
+    /// make sure there are at least `size` workers in the pool
+    pub(crate) fn grow(&mut self, size: usize) {
+        while self.workers < size {
+            let receiver = Arc::clone(&self.receiver);
+            thread::Builder::new()
+                .name(format!("scaffolding-worker-{}", self.workers))
+                .spawn(move || work(receiver))
+                .expect("failed to spawn a worker thread");
+            self.workers += 1;
+        }
+    }
+
+    pub(crate) fn execute<F>(&mut self, task: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.grow(1);
+        self.sender
+            .send(Box::new(task))
+            .expect("the workers only stop once the pool is dropped");
+    }
+}
+
+fn work(receiver: Arc<Mutex<mpsc::Receiver<Task>>>) {
+    loop {
+        // the lock is released as soon as a task has been received so
+        // the other workers can pick up the next one
+        let task = receiver.lock().unwrap().recv();
+        match task {
+            Ok(task) => task(),
+            Err(mpsc::RecvError) => break,
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{settings::Repeat, Outcome, TestResult};
+use std::{
+    borrow::Cow,
+    fmt::Write as _,
+    time::{Duration, Instant},
+};
+
+/// run the test as many times as the [`Repeat`] setting asks and fold
+/// the runs into one result
+///
+/// `run` runs the test once, it returns `None` if the test cannot run
+/// anymore.
+pub(crate) fn repeat<F>(repeat: Repeat, mut run: F) -> Option<TestResult>
+where
+    F: FnMut() -> Option<TestResult>,
+{
+    let instant = Instant::now();
+    let mut runs = Runs::default();
+    loop {
+        let more = match repeat {
+            Repeat::Times(times) => runs.count < times,
+            Repeat::For(budget) => instant.elapsed() < budget,
+        };
+        if runs.count > 0 && !more {
+            break;
+        }
+
+        match run() {
+            Some(result) => runs.push(result),
+            None => break,
+        }
+    }
+
+    runs.fold()
+}
+
+/// the runs of a test, folded as they complete
+#[derive(Default)]
+struct Runs {
+    count: usize,
+    passed: usize,
+    flaky: usize,
+    skipped: usize,
+    min: Option<Duration>,
+    max: Duration,
+    total: Duration,
+    /// the first run, replaced with the first failure if any
+    first: Option<(usize, TestResult)>,
+}
+
+impl Runs {
+    fn push(&mut self, result: TestResult) {
+        self.count += 1;
+        if result.is_flaky() {
+            self.flaky += 1;
+        }
+        if result.is_success() {
+            self.passed += 1;
+        } else if result.is_skipped() || result.is_ignored() {
+            self.skipped += 1;
+        }
+        self.min = Some(
+            self.min
+                .map_or(result.duration, |min| min.min(result.duration)),
+        );
+        self.max = self.max.max(result.duration);
+        self.total += result.duration;
+
+        let first_failure =
+            result.is_failure() && !matches!(&self.first, Some((_, first)) if first.is_failure());
+        if self.first.is_none() || first_failure {
+            self.first = Some((self.count, result));
+        }
+    }
+
+    fn fold(self) -> Option<TestResult> {
+        let (index, first) = self.first?;
+        if self.count == 1 {
+            return Some(first);
+        }
+
+        let failed = self.count - self.passed - self.skipped;
+        let mut details = format!(
+            "ran {count} times: {passed} passed, {failed} failed",
+            count = self.count,
+            passed = self.passed,
+            failed = failed,
+        );
+        if self.flaky > 0 {
+            let _ = write!(details, " ({} flaky)", self.flaky);
+        }
+        if self.skipped > 0 {
+            let _ = write!(details, ", {} skipped", self.skipped);
+        }
+        let _ = write!(
+            details,
+            "\nduration min {min:.2?}, mean {mean:.2?}, max {max:.2?}",
+            min = self.min.unwrap_or_default(),
+            mean = self.total / self.count as u32,
+            max = self.max,
+        );
+
+        let (outcome, short) = if first.is_failure() {
+            if !first.details.is_empty() {
+                let _ = write!(
+                    details,
+                    "\nfirst failure (run {index}):\n{details}",
+                    index = index,
+                    details = first.details.trim_end()
+                );
+            }
+            (first.outcome, first.short)
+        } else if self.flaky > 0 {
+            (Outcome::Flaky, Cow::Borrowed("Flaky"))
+        } else if self.passed > 0 {
+            (Outcome::Success, Cow::Borrowed("Success"))
+        } else {
+            (first.outcome, first.short)
+        };
+
+        Some(TestResult {
+            outcome,
+            short,
+            details: details.into(),
+            output: first.output,
+            duration: self.total,
+        })
+    }
+}
@@ -1,14 +1,21 @@
-use crate::{Ordering, TestName, TestResult, TestedResult};
+use crate::{test_tree, Ordering, Terminal, TestName, TestResult, TestTree, TestedResult};
 use console::style;
 use std::{
+    collections::BTreeMap,
     fmt,
     io::{self, Write},
     iter::FromIterator,
+    time::Duration,
 };
 
 pub struct Progress<O> {
     output: O,
     indent: usize,
+    /// the ignored tests of the current tree, reported once it completed
+    ignored: usize,
+    /// the seed of the shuffled groups reported last, only printed again
+    /// when it changes
+    seed: Option<u64>,
 }
 
 #[derive(Debug)]
@@ -19,6 +26,16 @@ pub enum Report {
     Test {
         name: TestName,
         result: TestResult,
+        /// the tags of the test and of its groups
+        tags: Vec<TestName>,
+    },
+    Setup {
+        name: TestName,
+        result: TestResult,
+    },
+    Teardown {
+        name: TestName,
+        result: TestResult,
     },
     Group {
         name: TestName,
@@ -27,9 +44,17 @@ pub enum Report {
     },
 }
 
-impl Progress<std::io::Stdout> {
+/// the results of the tests having a tag, see [`Report::by_tag`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TagSummary {
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+}
+
+impl Progress<Terminal> {
     pub fn stdout() -> Self {
-        Self::new_with(std::io::stdout())
+        Self::new_with(Terminal::default())
     }
 }
 
@@ -42,17 +67,24 @@ where
     const TIME_PRECISION: usize = 2;
 
     pub fn new_with(output: O) -> Self {
-        Self { output, indent: 0 }
+        Self {
+            output,
+            indent: 0,
+            ignored: 0,
+            seed: None,
+        }
     }
 
     fn handle_result(
         &mut self,
         name: impl fmt::Display,
+        kind: &str,
         result: &TestResult,
+        tags: &[TestName],
     ) -> std::io::Result<()> {
         writeln!(
             &mut self.output,
-            "{indent:indent_level$}{result:.<7}{duration:.>18} {name}",
+            "{indent:indent_level$}{result:.<7}{duration:.>18} {kind}{name}{tags}",
             indent = Self::INDENT_UNIT,
             indent_level = self.indent * Self::INDENT_GROWTH,
             duration = format!(
@@ -60,16 +92,24 @@ where
                 precision = Self::TIME_PRECISION,
                 duration = result.duration
             ),
-            result = if result.is_success() {
+            result = if result.is_flaky() {
+                style(&result.short).yellow().bright()
+            } else if result.is_success() {
                 style(&result.short).green().bright()
-            } else if result.is_skipped() {
+            } else if result.is_skipped() || result.is_ignored() {
                 style(&result.short).yellow()
-            } else if result.is_timeout() {
+            } else if result.is_timeout() || result.is_leaked() {
                 style(&result.short).magenta()
             } else {
                 style(&result.short).red().bright()
             },
+            kind = style(kind).white().dim(),
             name = style(name).white().bold(),
+            tags = if tags.is_empty() {
+                String::new()
+            } else {
+                format!(" {}", style(format!("[{}]", tags.join(", "))).white().dim())
+            },
         )?;
         if !result.details.is_empty() {
             writeln!(
@@ -80,18 +120,47 @@ where
                 details = style(&result.details).white().dim(),
             )?;
         }
+        if (!result.is_success() || result.is_flaky()) && !result.output.is_empty() {
+            writeln!(
+                &mut self.output,
+                "{indent:indent_level$}{header}\n{output}",
+                indent = Self::INDENT_UNIT,
+                indent_level = self.indent * Self::INDENT_GROWTH,
+                header = style("---- output ----").white().dim(),
+                output = result.output.trim_end(),
+            )?;
+        }
 
         Ok(())
     }
 
-    fn handle_group_start(&mut self, name: impl fmt::Display) -> std::io::Result<()> {
-        writeln!(
+    fn handle_group_start(
+        &mut self,
+        name: impl fmt::Display,
+        ordering: &Ordering,
+    ) -> std::io::Result<()> {
+        write!(
             &mut self.output,
             "{indent:indent_level$}{name}",
             indent = Self::INDENT_UNIT,
             indent_level = self.indent * Self::INDENT_GROWTH,
             name = style(name).white().bold(),
         )?;
+        let seed = match *ordering {
+            Ordering::Shuffled { seed: Some(seed) } if self.seed != Some(seed) => Some(seed),
+            _ => None,
+        };
+        if let Some(seed) = seed {
+            self.seed = Some(seed);
+            write!(
+                &mut self.output,
+                " {seed}",
+                seed = style(format!("(shuffled with seed {})", seed))
+                    .white()
+                    .dim(),
+            )?;
+        }
+        writeln!(&mut self.output)?;
 
         self.indent = self.indent.saturating_add(1);
 
@@ -100,13 +169,30 @@ where
 
     fn handle_group_end(&mut self) -> std::io::Result<()> {
         self.indent = self.indent.saturating_sub(1);
+        if self.indent == 0 && self.ignored > 0 {
+            writeln!(
+                &mut self.output,
+                "{ignored}",
+                ignored = style(format!("{} ignored", self.ignored)).yellow(),
+            )?;
+            self.ignored = 0;
+        }
         Ok(())
     }
 
     pub fn handle(&mut self, result: &TestedResult) -> std::io::Result<()> {
         match result {
-            TestedResult::Single { name, result } => self.handle_result(name, result),
-            TestedResult::GroupStart { name, .. } => self.handle_group_start(name),
+            TestedResult::Single { name, result, tags } => {
+                if result.is_ignored() {
+                    self.ignored += 1;
+                }
+                self.handle_result(name, "", result, tags)
+            }
+            TestedResult::Setup { name, result } => self.handle_result(name, "setup ", result, &[]),
+            TestedResult::Teardown { name, result } => {
+                self.handle_result(name, "teardown ", result, &[])
+            }
+            TestedResult::GroupStart { name, ordering } => self.handle_group_start(name, ordering),
             TestedResult::GroupEnd { .. } => self.handle_group_end(),
         }
     }
@@ -118,24 +204,159 @@ impl Report {
         match self {
             Self::Grouped { reports } => reports.push(report),
             Self::Group { reports, .. } => reports.push(report),
-            Self::Test { .. } => panic!("we should not try to push a result to a single test"),
+            Self::Test { .. } | Self::Setup { .. } | Self::Teardown { .. } => {
+                panic!("we should not try to push a result to a single test")
+            }
         }
     }
 
     pub fn is_success(&self) -> bool {
         match self {
             Report::Grouped { reports } => reports.iter().all(|r| r.is_success()),
-            Report::Test { result, .. } => result.is_success(),
+            Report::Test { result, .. }
+            | Report::Setup { result, .. }
+            | Report::Teardown { result, .. } => result.is_success() || result.is_ignored(),
             Report::Group { reports, .. } => reports.iter().all(|r| r.is_success()),
         }
     }
 
+    /// merge the reports of the shards of a run (see [`Shard`]) into one,
+    /// in the order of the given tests
+    ///
+    /// The groups with the same path are merged together, a test, a setup
+    /// or a teardown reported by more than one shard is only kept once,
+    /// failed if it failed on any of the shards. The shards only know the
+    /// order of their own tests, the merged report follows the order of
+    /// `tests`, the tree the shards were executed from.
+    ///
+    /// [`Shard`]: crate::Shard
+    pub fn merge<I>(tests: &TestTree, reports: I) -> Self
+    where
+        I: IntoIterator<Item = Report>,
+    {
+        let mut merged = Vec::new();
+        for report in reports {
+            match report {
+                Report::Grouped { reports } => merge_into(&mut merged, reports),
+                report => merge_into(&mut merged, vec![report]),
+            }
+        }
+        sort_like(&mut merged, std::slice::from_ref(tests));
+
+        if merged.len() == 1 {
+            merged.pop().unwrap()
+        } else {
+            Self::Grouped { reports: merged }
+        }
+    }
+
+    /// the duration of every test, by path
+    pub fn durations(&self) -> BTreeMap<String, Duration> {
+        let mut durations = BTreeMap::new();
+        self.collect_durations("", &mut durations);
+        durations
+    }
+
+    fn collect_durations(&self, path: &str, durations: &mut BTreeMap<String, Duration>) {
+        let path_of = |name: &TestName| test_tree::join(path, name);
+        match self {
+            Report::Grouped { reports } => {
+                for report in reports {
+                    report.collect_durations(path, durations);
+                }
+            }
+            Report::Group { name, reports, .. } => {
+                let path = path_of(name);
+                for report in reports {
+                    report.collect_durations(&path, durations);
+                }
+            }
+            Report::Test { name, result, .. } => {
+                if !result.is_skipped() && !result.is_ignored() {
+                    durations.insert(path_of(name), result.duration);
+                }
+            }
+            Report::Setup { .. } | Report::Teardown { .. } => (),
+        }
+    }
+
+    /// write the duration of every test, to balance the shards of the
+    /// next runs (see [`Shard::balanced`])
+    ///
+    /// [`Shard::balanced`]: crate::Shard::balanced
+    pub fn write_durations<O: Write>(&self, output: &mut O) -> io::Result<()> {
+        for (path, duration) in self.durations() {
+            writeln!(output, "{:.3} {}", duration.as_secs_f64() * 1_000.0, path)?;
+        }
+        Ok(())
+    }
+
+    /// the number of tests ignored or todo
+    pub fn ignored(&self) -> usize {
+        match self {
+            Report::Grouped { reports } | Report::Group { reports, .. } => {
+                reports.iter().map(Report::ignored).sum()
+            }
+            Report::Test { result, .. } => usize::from(result.is_ignored()),
+            Report::Setup { .. } | Report::Teardown { .. } => 0,
+        }
+    }
+
+    /// the results of the tests broken down by tag
+    ///
+    /// a test is counted once for each of its tags, the flaky tests are
+    /// counted as passed.
+    pub fn by_tag(&self) -> BTreeMap<TestName, TagSummary> {
+        let mut summaries = BTreeMap::new();
+        self.tally(&mut summaries);
+        summaries
+    }
+
+    fn tally(&self, summaries: &mut BTreeMap<TestName, TagSummary>) {
+        match self {
+            Report::Grouped { reports } | Report::Group { reports, .. } => {
+                for report in reports {
+                    report.tally(summaries);
+                }
+            }
+            Report::Test { result, tags, .. } => {
+                for tag in tags {
+                    let summary: &mut TagSummary = summaries.entry(tag.clone()).or_default();
+                    if result.is_success() {
+                        summary.passed += 1;
+                    } else if result.is_skipped() || result.is_ignored() {
+                        summary.skipped += 1;
+                    } else {
+                        summary.failed += 1;
+                    }
+                }
+            }
+            Report::Setup { .. } | Report::Teardown { .. } => (),
+        }
+    }
+
     fn report_<O: Write>(&self, indent: &str, output: &mut O) -> io::Result<()> {
         let next_indent = format!("{current}#", current = indent);
         beard::beard! {
             output,
-            if let Report::Test { name, result } = (self) {
-                "1. `" { name } "` (" { format!("{:?}", result.duration) } "): **" { &result.short } "**\n"
+            if let Report::Test { name, result, tags } = (self) {
+                "1. `" { name } "`"
+                if (!tags.is_empty()) {
+                    " [" { tags.join(", ") } "]"
+                }
+                " (" { format!("{:?}", result.duration) } "): **" { &result.short } "**\n"
+                if (!result.details.is_empty()) {
+                    "   "{ &result.details } "\n"
+                }
+            }
+            if let Report::Setup { name, result } = (self) {
+                "1. setup `" { name } "` (" { format!("{:?}", result.duration) } "): **" { &result.short } "**\n"
+                if (!result.details.is_empty()) {
+                    "   "{ &result.details } "\n"
+                }
+            }
+            if let Report::Teardown { name, result } = (self) {
+                "1. teardown `" { name } "` (" { format!("{:?}", result.duration) } "): **" { &result.short } "**\n"
                 if (!result.details.is_empty()) {
                     "   "{ &result.details } "\n"
                 }
@@ -145,8 +366,12 @@ impl Report {
                     || { report.report_(&next_indent, output)? }
                 }
             }
-            if let Report::Group { name, reports, .. } = (self) {
-                { indent } " " {name} " (" { reports.len() } ")" "\n"
+            if let Report::Group { name, reports, ordering } = (self) {
+                { indent } " " {name} " (" { reports.len() } ")"
+                if let Ordering::Shuffled { seed: Some(seed) } = (ordering) {
+                    " shuffled with seed `" { seed } "`"
+                }
+                "\n"
                 "\n"
                 for report in (reports) {
                     || { report.report_(&next_indent, output)? }
@@ -157,7 +382,122 @@ impl Report {
     }
 
     pub fn report<O: Write>(&self, output: &mut O) -> io::Result<()> {
-        self.report_("#", output)
+        self.report_("#", output)?;
+
+        let ignored = self.ignored();
+        if ignored > 0 {
+            writeln!(output, "\n{} ignored", ignored)?;
+        }
+
+        let by_tag = self.by_tag();
+        if !by_tag.is_empty() {
+            writeln!(output, "\n# tags\n")?;
+            writeln!(output, "| tag | passed | failed | skipped |")?;
+            writeln!(output, "| --- | ---: | ---: | ---: |")?;
+            for (tag, summary) in by_tag {
+                writeln!(
+                    output,
+                    "| `{tag}` | {passed} | {failed} | {skipped} |",
+                    tag = tag,
+                    passed = summary.passed,
+                    failed = summary.failed,
+                    skipped = summary.skipped,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// merge the reports of a shard into the reports of the other shards
+fn merge_into(merged: &mut Vec<Report>, reports: Vec<Report>) {
+    for report in reports {
+        match report {
+            Report::Group {
+                name,
+                ordering,
+                reports,
+            } => {
+                let existing = merged.iter_mut().find_map(|merged| match merged {
+                    Report::Group {
+                        name: existing,
+                        reports,
+                        ..
+                    } if *existing == name => Some(reports),
+                    _ => None,
+                });
+                match existing {
+                    Some(existing) => merge_into(existing, reports),
+                    None => {
+                        let mut group = Vec::new();
+                        merge_into(&mut group, reports);
+                        merged.push(Report::Group {
+                            name,
+                            ordering,
+                            reports: group,
+                        });
+                    }
+                }
+            }
+            report => {
+                let existing = merged.iter_mut().find(|merged| same_test(merged, &report));
+                match existing {
+                    Some(existing) => {
+                        if !report.is_success() && existing.is_success() {
+                            *existing = report;
+                        }
+                    }
+                    None => merged.push(report),
+                }
+            }
+        }
+    }
+}
+
+/// returns `true` if both reports are the same test, the same setup or the
+/// same teardown
+fn same_test(left: &Report, right: &Report) -> bool {
+    match (left, right) {
+        (Report::Test { name: left, .. }, Report::Test { name: right, .. })
+        | (Report::Setup { name: left, .. }, Report::Setup { name: right, .. })
+        | (Report::Teardown { name: left, .. }, Report::Teardown { name: right, .. }) => {
+            left == right
+        }
+        _ => false,
+    }
+}
+
+/// put the reports of a group in the order of its tests, the setups first
+/// and the teardowns last like the executor reports them
+fn sort_like(reports: &mut [Report], tests: &[TestTree]) {
+    let position = |report: &Report| {
+        tests.iter().position(|test| match (report, test) {
+            (Report::Test { name, .. }, TestTree::Single { name: test, .. })
+            | (Report::Setup { name, .. }, TestTree::Setup { name: test, .. })
+            | (Report::Teardown { name, .. }, TestTree::Teardown { name: test, .. })
+            | (Report::Group { name, .. }, TestTree::Group { name: test, .. }) => name == test,
+            _ => false,
+        })
+    };
+    reports.sort_by_key(|report| {
+        let rank = match report {
+            Report::Setup { .. } => 0,
+            Report::Teardown { .. } => 2,
+            _ => 1,
+        };
+        (rank, position(report).unwrap_or(usize::MAX))
+    });
+
+    for report in reports.iter_mut() {
+        if let Report::Group { name, reports, .. } = report {
+            let group = tests.iter().find_map(|test| match test {
+                TestTree::Group {
+                    name: group, tests, ..
+                } if group == name => Some(tests.as_slice()),
+                _ => None,
+            });
+            sort_like(reports, group.unwrap_or_default());
+        }
     }
 }
 
@@ -177,8 +517,14 @@ impl FromIterator<TestedResult> for Report {
 
         for result in results {
             match result {
-                TestedResult::Single { name, result } => {
-                    push!(reports, Self::Test { name, result });
+                TestedResult::Single { name, result, tags } => {
+                    push!(reports, Self::Test { name, result, tags });
+                }
+                TestedResult::Setup { name, result } => {
+                    push!(reports, Self::Setup { name, result });
+                }
+                TestedResult::Teardown { name, result } => {
+                    push!(reports, Self::Teardown { name, result });
                 }
                 TestedResult::GroupStart { name, ordering } => {
                     let report = Self::Group {
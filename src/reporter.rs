@@ -0,0 +1,479 @@
+use crate::{
+    isolation, Executor, Html, JUnit, Json, Ordering, Progress, Report, Tap, TestName, TestedResult,
+};
+use std::{
+    env, fmt,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+/// environment variable selecting the reporters, see [`from_env`]
+pub(crate) const REPORTER_ENV: &str = "SCAFFOLDING_REPORTER";
+
+/// receives the events of a run as the results are yielded
+///
+/// The events come in the order of the [`TestTree`]: `run_start` first,
+/// `plan` with what is about to be executed, then for every group
+/// `group_start`, the events of its content and `group_end`, and
+/// `run_end` last. Every test gets a `test_start` before its
+/// `test_result`, the setups and teardowns only get a `test_result`.
+///
+/// Every hook does nothing by default so a reporter only implements the
+/// ones it is interested in. Several reporters can receive the same run
+/// with [`FanOut`].
+///
+/// [`TestTree`]: crate::TestTree
+pub trait Reporter {
+    /// the run is about to start
+    fn run_start(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// what is about to be executed, right after [`Reporter::run_start`]
+    fn plan(&mut self, plan: &Plan) -> io::Result<()> {
+        let _ = plan;
+        Ok(())
+    }
+
+    /// the results of the group come next
+    fn group_start(&mut self, name: &TestName, ordering: &Ordering) -> io::Result<()> {
+        let _ = (name, ordering);
+        Ok(())
+    }
+
+    /// all the results of the group were reported
+    fn group_end(&mut self, name: &TestName, ordering: &Ordering) -> io::Result<()> {
+        let _ = (name, ordering);
+        Ok(())
+    }
+
+    /// the result of the test comes next
+    fn test_start(&mut self, name: &TestName) -> io::Result<()> {
+        let _ = name;
+        Ok(())
+    }
+
+    /// the result of a test, a setup or a teardown
+    ///
+    /// this is never a [`TestedResult::GroupStart`] nor a
+    /// [`TestedResult::GroupEnd`], see [`Reporter::group_start`] and
+    /// [`Reporter::group_end`].
+    fn test_result(&mut self, result: &TestedResult) -> io::Result<()> {
+        let _ = result;
+        Ok(())
+    }
+
+    /// the run completed
+    fn run_end(&mut self, summary: &Summary) -> io::Result<()> {
+        let _ = summary;
+        Ok(())
+    }
+}
+
+impl<R: Reporter + ?Sized> Reporter for Box<R> {
+    fn run_start(&mut self) -> io::Result<()> {
+        (**self).run_start()
+    }
+    fn plan(&mut self, plan: &Plan) -> io::Result<()> {
+        (**self).plan(plan)
+    }
+    fn group_start(&mut self, name: &TestName, ordering: &Ordering) -> io::Result<()> {
+        (**self).group_start(name, ordering)
+    }
+    fn group_end(&mut self, name: &TestName, ordering: &Ordering) -> io::Result<()> {
+        (**self).group_end(name, ordering)
+    }
+    fn test_start(&mut self, name: &TestName) -> io::Result<()> {
+        (**self).test_start(name)
+    }
+    fn test_result(&mut self, result: &TestedResult) -> io::Result<()> {
+        (**self).test_result(result)
+    }
+    fn run_end(&mut self, summary: &Summary) -> io::Result<()> {
+        (**self).run_end(summary)
+    }
+}
+
+impl<O: Write> Reporter for Progress<O> {
+    fn group_start(&mut self, name: &TestName, ordering: &Ordering) -> io::Result<()> {
+        self.handle(&TestedResult::GroupStart {
+            name: name.clone(),
+            ordering: *ordering,
+        })
+    }
+
+    fn group_end(&mut self, name: &TestName, ordering: &Ordering) -> io::Result<()> {
+        self.handle(&TestedResult::GroupEnd {
+            name: name.clone(),
+            ordering: *ordering,
+        })
+    }
+
+    fn test_result(&mut self, result: &TestedResult) -> io::Result<()> {
+        self.handle(result)
+    }
+}
+
+/// sends the events of a run to every one of its reporters
+///
+/// ```
+/// use scaffolding::{FanOut, Progress};
+///
+/// let reporter = FanOut::new()
+///     .with(Progress::stdout())
+///     .with(Progress::new_with(Vec::new()));
+/// # let _ = reporter;
+/// ```
+///
+/// The reporters receive every event in the order they were added, the
+/// first error stops the event from reaching the following reporters.
+#[derive(Default)]
+pub struct FanOut {
+    reporters: Vec<Box<dyn Reporter + Send>>,
+}
+
+impl FanOut {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// add a reporter receiving the events after the previous ones
+    pub fn with<R>(mut self, reporter: R) -> Self
+    where
+        R: Reporter + Send + 'static,
+    {
+        self.push(Box::new(reporter));
+        self
+    }
+
+    pub fn push(&mut self, reporter: Box<dyn Reporter + Send>) {
+        self.reporters.push(reporter);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.reporters.is_empty()
+    }
+}
+
+impl fmt::Debug for FanOut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FanOut")
+            .field("reporters", &self.reporters.len())
+            .finish()
+    }
+}
+
+impl Reporter for FanOut {
+    fn run_start(&mut self) -> io::Result<()> {
+        self.reporters.iter_mut().try_for_each(|r| r.run_start())
+    }
+    fn plan(&mut self, plan: &Plan) -> io::Result<()> {
+        self.reporters.iter_mut().try_for_each(|r| r.plan(plan))
+    }
+    fn group_start(&mut self, name: &TestName, ordering: &Ordering) -> io::Result<()> {
+        self.reporters
+            .iter_mut()
+            .try_for_each(|r| r.group_start(name, ordering))
+    }
+    fn group_end(&mut self, name: &TestName, ordering: &Ordering) -> io::Result<()> {
+        self.reporters
+            .iter_mut()
+            .try_for_each(|r| r.group_end(name, ordering))
+    }
+    fn test_start(&mut self, name: &TestName) -> io::Result<()> {
+        self.reporters
+            .iter_mut()
+            .try_for_each(|r| r.test_start(name))
+    }
+    fn test_result(&mut self, result: &TestedResult) -> io::Result<()> {
+        self.reporters
+            .iter_mut()
+            .try_for_each(|r| r.test_result(result))
+    }
+    fn run_end(&mut self, summary: &Summary) -> io::Result<()> {
+        self.reporters
+            .iter_mut()
+            .try_for_each(|r| r.run_end(summary))
+    }
+}
+
+/// what a run is about to execute, given to [`Reporter::plan`]
+#[derive(Debug, Default, Clone)]
+pub struct Plan {
+    tests: usize,
+    filtered_out: usize,
+}
+
+impl Plan {
+    fn of(executor: &Executor) -> Self {
+        Self {
+            tests: executor.tests(),
+            filtered_out: executor.filtered_out(),
+        }
+    }
+
+    /// the number of tests to report, setups and teardowns excluded
+    pub fn tests(&self) -> usize {
+        self.tests
+    }
+
+    /// the number of tests left out of the run by the filter, the tags,
+    /// the shard or the selection of the ignored tests
+    pub fn filtered_out(&self) -> usize {
+        self.filtered_out
+    }
+}
+
+/// a reporter ignoring every event
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Quiet;
+
+impl Reporter for Quiet {}
+
+/// the counts of a completed run, given to [`Reporter::run_end`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Summary {
+    /// the tests that succeeded, flaky ones included
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    /// the tests ignored or todo
+    pub ignored: usize,
+    /// the tests that succeeded after being retried
+    pub flaky: usize,
+    /// the setups and teardowns that failed
+    pub hooks_failed: usize,
+    /// the tests, setups and teardowns that did not stop on time
+    pub leaked: usize,
+    pub duration: Duration,
+}
+
+impl Summary {
+    /// count the given result
+    pub fn record(&mut self, result: &TestedResult) {
+        match result {
+            TestedResult::Single { result, .. } => {
+                if result.is_ignored() {
+                    self.ignored += 1;
+                } else if result.is_skipped() {
+                    self.skipped += 1;
+                } else if result.is_success() {
+                    self.passed += 1;
+                    if result.is_flaky() {
+                        self.flaky += 1;
+                    }
+                } else {
+                    self.failed += 1;
+                }
+                if result.is_leaked() {
+                    self.leaked += 1;
+                }
+            }
+            TestedResult::Setup { result, .. } | TestedResult::Teardown { result, .. } => {
+                if result.is_failure() {
+                    self.hooks_failed += 1;
+                }
+                if result.is_leaked() {
+                    self.leaked += 1;
+                }
+            }
+            TestedResult::GroupStart { .. } | TestedResult::GroupEnd { .. } => (),
+        }
+    }
+
+    /// the tests that were executed, neither skipped nor ignored
+    pub fn tested(&self) -> usize {
+        self.passed + self.failed
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.failed == 0 && self.hooks_failed == 0
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{passed} passed, {failed} failed",
+            passed = self.passed,
+            failed = self.failed
+        )?;
+        for (count, what) in [
+            (self.skipped, "skipped"),
+            (self.ignored, "ignored"),
+            (self.flaky, "flaky"),
+            (self.hooks_failed, "setup or teardown failed"),
+            (self.leaked, "leaked"),
+        ] {
+            if count > 0 {
+                write!(f, ", {} {}", count, what)?;
+            }
+        }
+        write!(f, " in {:.2?}", self.duration)
+    }
+}
+
+/// the reporters given to the process, if any
+///
+/// `SCAFFOLDING_REPORTER` is a comma separated list of reporters, each
+/// one a name optionally followed by `:` and an argument:
+///
+/// * `progress` prints the progress of the tests on the standard output
+/// * `quiet` prints nothing
+/// * `junit:<path>` writes the reports as JUnit XML in the file at the
+///   path, `junit.xml` by default (see [`JUnit`])
+/// * `tap` prints the results in TAP on the standard output, `tap:<path>`
+///   writes them in the file at the path (see [`Tap`])
+/// * `json` prints the events as JSON lines in the format of the test
+///   harness on the standard output, `json:<path>` writes them in the file
+///   at the path (see [`Json`])
+/// * `html:<path>` writes the reports as an HTML page in the file at the
+///   path, `report.html` by default (see [`Html`])
+///
+/// # panics
+///
+/// if one of the reporters is unknown or invalid
+pub(crate) fn from_env() -> Option<Box<dyn Reporter + Send>> {
+    // the process of an isolated test only sends its result to the parent
+    // process, it must not create the files of the parent's reporters
+    if isolation::selected().is_some() {
+        return Some(Box::new(Quiet));
+    }
+
+    let reporters = env::var(REPORTER_ENV).ok()?;
+    let mut fan_out = FanOut::new();
+    for reporter in reporters.split(',').map(str::trim) {
+        if reporter.is_empty() {
+            continue;
+        }
+        let (name, argument) = match reporter.split_once(':') {
+            Some((name, argument)) => (name.trim(), Some(argument.trim())),
+            None => (reporter, None),
+        };
+        fan_out.push(
+            named(name, argument).unwrap_or_else(|error| {
+                panic!("invalid {}: {:?} {}", REPORTER_ENV, reporter, error)
+            }),
+        );
+    }
+
+    match fan_out.reporters.len() {
+        0 => None,
+        1 => fan_out.reporters.pop(),
+        _ => Some(Box::new(fan_out)),
+    }
+}
+
+/// the reporter with the given name
+fn named(name: &str, argument: Option<&str>) -> Result<Box<dyn Reporter + Send>, String> {
+    let without_argument = |reporter: Box<dyn Reporter + Send>| match argument {
+        None => Ok(reporter),
+        Some(_) => Err("does not take an argument".to_owned()),
+    };
+    match name {
+        "progress" => without_argument(Box::new(Progress::stdout())),
+        "quiet" => without_argument(Box::new(Quiet)),
+        "junit" => Ok(Box::new(JUnit::new(argument.unwrap_or("junit.xml")))),
+        "tap" => match argument {
+            None => Ok(Box::new(Tap::stdout())),
+            Some(path) => open(path).map(|file| Box::new(Tap::new_with(file)) as _),
+        },
+        "json" => match argument {
+            None => Ok(Box::new(Json::stdout())),
+            Some(path) => open(path).map(|file| Box::new(Json::new_with(file)) as _),
+        },
+        "html" => Ok(Box::new(Html::new(argument.unwrap_or("report.html")))),
+        _ => Err(
+            "is not a reporter, expected one of: progress, quiet, junit, tap, json, html"
+                .to_owned(),
+        ),
+    }
+}
+
+/// the reports of the runs so far, for the reporters writing a whole
+/// [`Report`] at the end of every run
+#[derive(Debug, Default)]
+pub(crate) struct Collector {
+    reports: Vec<Report>,
+    /// the results of the current run
+    results: Vec<TestedResult>,
+}
+
+impl Collector {
+    pub(crate) fn push(&mut self, result: TestedResult) {
+        self.results.push(result);
+    }
+
+    /// complete the report of the current run, returns the reports of
+    /// all the runs
+    pub(crate) fn complete(&mut self) -> &[Report] {
+        let results = std::mem::take(&mut self.results);
+        if !results.is_empty() {
+            self.reports.push(results.into_iter().collect());
+        }
+        &self.reports
+    }
+}
+
+/// the file of a streaming reporter
+fn open(path: &str) -> Result<BufWriter<File>, String> {
+    create(Path::new(path))
+        .map(BufWriter::new)
+        .map_err(|error| format!("cannot create the file: {}", error))
+}
+
+/// create the file at the given path, and its directory if it does not
+/// exist
+pub(crate) fn create(path: &Path) -> io::Result<File> {
+    if let Some(directory) = path.parent() {
+        if !directory.as_os_str().is_empty() {
+            fs::create_dir_all(directory)?;
+        }
+    }
+    File::create(path)
+}
+
+/// the reporters given to the process or the [`Progress`] on the
+/// standard output
+pub(crate) fn from_env_or_progress() -> Box<dyn Reporter + Send> {
+    from_env().unwrap_or_else(|| Box::new(Progress::stdout()))
+}
+
+/// execute the tests, sending the events to the reporter and every result
+/// to `each`
+pub(crate) fn run<R, F>(
+    mut executor: Executor,
+    reporter: &mut R,
+    mut each: F,
+) -> io::Result<Summary>
+where
+    R: Reporter + ?Sized,
+    F: FnMut(TestedResult),
+{
+    let started = Instant::now();
+    let mut summary = Summary::default();
+    reporter.run_start()?;
+    reporter.plan(&Plan::of(&executor))?;
+    loop {
+        if let Some(name) = executor.upcoming_test() {
+            reporter.test_start(name)?;
+        }
+        let result = match executor.next() {
+            Some(result) => result,
+            None => break,
+        };
+        summary.record(&result);
+        match &result {
+            TestedResult::GroupStart { name, ordering } => reporter.group_start(name, ordering)?,
+            TestedResult::GroupEnd { name, ordering } => reporter.group_end(name, ordering)?,
+            _ => reporter.test_result(&result)?,
+        }
+        each(result);
+    }
+    summary.duration = started.elapsed();
+    reporter.run_end(&summary)?;
+    Ok(summary)
+}
@@ -5,16 +5,22 @@ use std::{borrow::Cow, fmt, time::Duration};
 ///
 /// it contains all the details associated to running the test
 /// such as the outcome, the duration and eventually some details
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TestResult {
     pub outcome: Outcome,
     pub short: Cow<'static, str>,
     pub details: Cow<'static, str>,
+    /// what the test wrote on its standard output and error, see
+    /// [`Capture`]
+    ///
+    /// [`Capture`]: crate::settings::Capture
+    pub output: Cow<'static, str>,
     pub duration: Duration,
 }
 
 impl TestResult {
-    /// returns `true` if the `outcome` is [`Outcome::Success`]
+    /// returns `true` if the `outcome` is [`Outcome::Success`] or
+    /// [`Outcome::Flaky`]
     ///
     /// this is similar to testing:
     ///
@@ -23,7 +29,7 @@ impl TestResult {
     /// # let result = TestResult::passed("");
     ///
     /// # let is_success =
-    /// matches!(result.outcome, Outcome::Success);
+    /// matches!(result.outcome, Outcome::Success | Outcome::Flaky);
     /// # assert!(is_success, "test that are _passed_ should have a `Outcome::Success`")
     /// ```
     #[inline]
@@ -31,16 +37,31 @@ impl TestResult {
         self.outcome.is_success()
     }
 
+    #[inline]
+    pub fn is_flaky(&self) -> bool {
+        self.outcome.is_flaky()
+    }
+
     #[inline]
     pub fn is_timeout(&self) -> bool {
         self.outcome.is_timeout()
     }
 
+    #[inline]
+    pub fn is_leaked(&self) -> bool {
+        self.outcome.is_leaked()
+    }
+
     #[inline]
     pub fn is_skipped(&self) -> bool {
         self.outcome.is_skipped()
     }
 
+    #[inline]
+    pub fn is_ignored(&self) -> bool {
+        self.outcome.is_ignored()
+    }
+
     #[inline]
     pub fn is_failure(&self) -> bool {
         self.outcome.is_failure()
@@ -58,6 +79,7 @@ impl TestResult {
             outcome: Outcome::Success,
             short: Cow::Borrowed("Success"),
             details: details.into(),
+            output: Cow::Borrowed(""),
             duration: Duration::from_secs(0),
         }
     }
@@ -76,6 +98,7 @@ impl TestResult {
             },
             short: Cow::Borrowed("Failure"),
             details: details.into(),
+            output: Cow::Borrowed(""),
             duration: Duration::from_secs(0),
         }
     }
@@ -86,10 +109,36 @@ impl TestResult {
             outcome: Outcome::Skipped,
             short: Cow::Borrowed("Skipped"),
             details: Cow::Borrowed(""),
+            output: Cow::Borrowed(""),
+            duration: Duration::from_secs(0),
+        }
+    }
+
+    /// create a standard [`TestResult`] for a test ignored for the given
+    /// _reason_, it is okay to leave it empty though.
+    #[inline]
+    pub fn ignored<D>(reason: D) -> Self
+    where
+        D: Into<Cow<'static, str>>,
+    {
+        Self {
+            outcome: Outcome::Ignored,
+            short: Cow::Borrowed("Ignored"),
+            details: reason.into(),
+            output: Cow::Borrowed(""),
             duration: Duration::from_secs(0),
         }
     }
 
+    /// create a standard [`TestResult`] for a test not implemented yet
+    #[inline]
+    pub fn todo() -> Self {
+        Self {
+            short: Cow::Borrowed("Todo"),
+            ..Self::ignored("")
+        }
+    }
+
     /// create a standard [`TestResult`] for failed test. You can add
     /// some _details_ (a description of what happened), it is okay to
     /// leave it empty though.
@@ -104,6 +153,26 @@ impl TestResult {
             },
             short: Cow::Borrowed("Timeout"),
             details: details.into(),
+            output: Cow::Borrowed(""),
+            duration: Duration::from_secs(0),
+        }
+    }
+
+    /// create a standard [`TestResult`] for a test still running after
+    /// its timeout and grace period. You can add some _details_ (a
+    /// description of what happened), it is okay to leave it empty though.
+    #[inline]
+    pub fn leaked<D>(details: D) -> Self
+    where
+        D: Into<Cow<'static, str>>,
+    {
+        Self {
+            outcome: Outcome::Failure {
+                reason: FailureReason::Leaked,
+            },
+            short: Cow::Borrowed("Leaked"),
+            details: details.into(),
+            output: Cow::Borrowed(""),
             duration: Duration::from_secs(0),
         }
     }
@@ -0,0 +1,65 @@
+use crate::{settings::Retry, Outcome, TestResult};
+use std::{borrow::Cow, fmt::Write as _, thread, time::Duration};
+
+/// run the test again while it fails, as many times as the [`Retry`]
+/// setting allows
+///
+/// `attempt` runs the test once, it returns `None` if the test cannot
+/// run anymore.
+pub(crate) fn retry<F>(retry: Retry, mut attempt: F) -> Option<TestResult>
+where
+    F: FnMut() -> Option<TestResult>,
+{
+    let attempts = retry.attempts.max(1);
+    let mut results = Vec::new();
+    while let Some(result) = attempt() {
+        let done = !result.is_failure() || results.len() + 1 >= attempts;
+        results.push(result);
+        if done {
+            break;
+        }
+        thread::sleep(retry.backoff);
+    }
+
+    if results.len() > 1 {
+        Some(fold(results))
+    } else {
+        results.pop()
+    }
+}
+
+/// the result of the last attempt, with the details of all of them
+fn fold(results: Vec<TestResult>) -> TestResult {
+    let mut details = String::new();
+    let mut output = String::new();
+    let mut duration = Duration::default();
+    for (index, result) in results.iter().enumerate() {
+        let _ = writeln!(
+            details,
+            "attempt {attempt}: {short} after {duration:.2?}",
+            attempt = index + 1,
+            short = result.short,
+            duration = result.duration,
+        );
+        if !result.details.is_empty() {
+            let _ = writeln!(details, "{}", result.details.trim_end());
+        }
+        output.push_str(&result.output);
+        duration += result.duration;
+    }
+
+    let last = results.into_iter().last().expect("at least two attempts");
+    let (outcome, short) = if last.is_success() {
+        (Outcome::Flaky, Cow::Borrowed("Flaky"))
+    } else {
+        (last.outcome, last.short)
+    };
+
+    TestResult {
+        outcome,
+        short,
+        details: details.trim_end().to_owned().into(),
+        output: output.into(),
+        duration,
+    }
+}
@@ -0,0 +1,95 @@
+use std::{
+    collections::hash_map::RandomState,
+    env,
+    hash::{BuildHasher, Hasher},
+    time::SystemTime,
+};
+
+/// environment variable to set the seed of every shuffled group
+/// and of the random generators of the tests
+pub(crate) const SEED_ENV: &str = "SCAFFOLDING_SEED";
+
+/// small pseudo random number generator (splitmix64)
+///
+/// This is not meant for anything else than making a run of tests
+/// reproducible: the same seed will always produce the same sequence.
+/// It is not suitable for cryptographic use.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// a number in the range `0..bound`
+    ///
+    /// # panics
+    ///
+    /// if `bound` is `0`
+    pub fn below(&mut self, bound: u64) -> u64 {
+        assert!(bound > 0, "cannot draw a number below 0");
+        self.next_u64() % bound
+    }
+
+    /// shuffle the given slice in place (Fisher-Yates)
+    pub fn shuffle<T>(&mut self, slice: &mut [T]) {
+        for i in (1..slice.len()).rev() {
+            let j = self.below(i as u64 + 1) as usize;
+            slice.swap(i, j);
+        }
+    }
+}
+
+/// derive the seed of a test from the seed of the run and the test's path
+///
+/// the derivation only depends on its inputs (FNV-1a) so a test keeps
+/// the same seed from one run to the other.
+pub(crate) fn derive(seed: u64, path: &str) -> u64 {
+    Rng::new(seed ^ fnv1a(path)).next_u64()
+}
+
+/// the 64 bits FNV-1a hash, unlike the hashers of the standard library it
+/// is the same on every machine and with every version of Rust
+pub(crate) fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xCBF2_9CE4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01B3)
+    })
+}
+
+/// the seed set with the [`SEED_ENV`] environment variable, if any
+///
+/// # panics
+///
+/// if the environment variable is set but is not a valid `u64`
+pub(crate) fn seed_from_env() -> Option<u64> {
+    let seed = env::var(SEED_ENV).ok()?;
+    match seed.trim().parse() {
+        Ok(seed) => Some(seed),
+        Err(error) => panic!(
+            "invalid {env} ({seed:?}): {error}",
+            env = SEED_ENV,
+            seed = seed,
+            error = error
+        ),
+    }
+}
+
+/// draw a new seed, different at every call
+pub(crate) fn random_seed() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    if let Ok(now) = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+        hasher.write_u128(now.as_nanos());
+    }
+    hasher.finish()
+}
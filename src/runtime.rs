@@ -0,0 +1,101 @@
+use crate::{cancellation::Cancelled, CancellationToken};
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// run the future of an async test to completion on the current thread
+///
+/// The future is dropped as soon as the test is cancelled, returning
+/// `None`. Without the `with-tokio` feature the future is polled by a
+/// minimal executor parking the thread while the future is pending, it
+/// cannot drive the futures needing a specific runtime (like the timers
+/// and sockets of tokio). With the `with-tokio` feature, the future runs
+/// on a current thread tokio runtime with all its drivers enabled.
+pub(crate) fn block_on<F>(future: F, cancellation: &CancellationToken) -> Option<F::Output>
+where
+    F: Future,
+{
+    let future = Cancellable {
+        future: Box::pin(future),
+        cancelled: cancellation.cancelled(),
+    };
+    imp::block_on(future)
+}
+
+/// the future of a test, completing early when the test is cancelled
+struct Cancellable<F> {
+    future: Pin<Box<F>>,
+    cancelled: Cancelled,
+}
+
+impl<F> Future for Cancellable<F>
+where
+    F: Future,
+{
+    type Output = Option<F::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if Pin::new(&mut self.cancelled).poll(cx).is_ready() {
+            return Poll::Ready(None);
+        }
+        self.future.as_mut().poll(cx).map(Some)
+    }
+}
+
+#[cfg(not(feature = "with-tokio"))]
+mod imp {
+    use std::{
+        future::Future,
+        pin::Pin,
+        sync::Arc,
+        task::{Context, Poll, Wake, Waker},
+        thread::{self, Thread},
+    };
+
+    /// wakes the thread blocked on the future
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark()
+        }
+
+        fn wake_by_ref(self: &Arc<Self>) {
+            self.0.unpark()
+        }
+    }
+
+    pub(super) fn block_on<F>(mut future: F) -> F::Output
+    where
+        F: Future + Unpin,
+    {
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match Pin::new(&mut future).poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                // the thread may be unparked spuriously, the future is
+                // then polled again for nothing
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "with-tokio")]
+mod imp {
+    use std::future::Future;
+
+    pub(super) fn block_on<F>(future: F) -> F::Output
+    where
+        F: Future + Unpin,
+    {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("cannot start the tokio runtime of the test")
+            .block_on(future)
+    }
+}
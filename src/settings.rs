@@ -2,7 +2,9 @@ use std::{
     any::{type_name, Any, TypeId},
     collections::HashMap,
     fmt,
+    num::NonZeroUsize,
     sync::Arc,
+    thread,
     time::Duration,
 };
 
@@ -86,6 +88,182 @@ pub struct Timeout(pub Duration);
 #[derive(Debug, Clone, Copy)]
 pub struct StackSize(pub usize);
 
+/// how long a timed out test has to return once cancelled
+///
+/// A test still running after its [`Timeout`] and the grace period is
+/// reported as leaked: its thread is left behind and keeps running while
+/// the other tests execute.
+#[derive(Debug, Clone, Copy)]
+pub struct GracePeriod(pub Duration);
+
+/// run a failing test again
+///
+/// A failing [`single!`] test runs up to `attempts` times in total,
+/// waiting `backoff` between two attempts. A test passing on a later
+/// attempt is reported as [`Outcome::Flaky`], which still counts as a
+/// success. The details and the duration of every attempt are kept in
+/// the details of the result.
+///
+/// Only the tests that can run more than once are retried (see
+/// [`IsTest::run_and_keep`]), like the closures.
+///
+/// [`single!`]: crate::single
+/// [`Outcome::Flaky`]: crate::Outcome::Flaky
+/// [`IsTest::run_and_keep`]: crate::IsTest::run_and_keep
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Retry {
+    pub attempts: usize,
+    pub backoff: Duration,
+}
+
+/// run every test several times, to hunt rare failures
+///
+/// Every [`single!`] test runs the given number of times or, with
+/// [`Repeat::For`], until the given duration elapsed (at least once). The
+/// runs are folded into one result: failing if any of the run failed,
+/// with the number of runs that passed and failed, the details of the
+/// first failure and the minimum, mean and maximum durations of the runs.
+///
+/// Like with [`Retry`], only the tests that can run more than once are
+/// repeated. Every run is retried on its own.
+///
+/// [`single!`]: crate::single
+#[derive(Debug, Clone, Copy)]
+pub enum Repeat {
+    Times(usize),
+    For(Duration),
+}
+
+/// the environment variable enabling [`FailFast`] for the whole run
+pub(crate) const FAIL_FAST_ENV: &str = "SCAFFOLDING_FAIL_FAST";
+
+/// stop the whole run on the first failure
+///
+/// Once a test with this setting failed, none of the tests with this
+/// setting starts anymore, wherever they are in the tree: they are
+/// skipped with the path of the failed test as reason. The tests already
+/// running complete and the teardowns still run.
+///
+/// Default to the `SCAFFOLDING_FAIL_FAST` environment variable: enabled
+/// if it is set to anything else than `0` or `false`.
+#[derive(Debug, Clone, Copy)]
+pub struct FailFast(pub bool);
+
+/// how much of the backtrace of a panicking test is put in the details
+/// of its result
+///
+/// Default to the `RUST_BACKTRACE` environment variable, like the
+/// backtraces printed by the standard library: [`Backtrace::Off`] if
+/// it is not set or set to `0`, [`Backtrace::Full`] if it is set to
+/// `full` and [`Backtrace::Short`] otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backtrace {
+    /// only the message and the location of the panic
+    Off,
+    /// the frames of the test
+    Short,
+    /// all the frames, including the ones of the panic machinery and of
+    /// the runner
+    Full,
+}
+
+/// what to do with the output of the tests
+///
+/// With [`Capture::On`] or [`Capture::OnFailure`], the text the tests
+/// write on the standard output and error is attached to their
+/// [`TestResult`] (see [`TestResult::output`]) instead of being mixed
+/// with the progress of the run. [`Progress`] only prints it under the
+/// tests that did not succeed. The output is not captured by default.
+///
+/// The output is captured by redirecting the standard output and error of
+/// the process, so the tests capturing their output run one at a time,
+/// even the ones of different runs, and even if [`Jobs`] allows more: use
+/// [`Capture::Off`] to run them concurrently. The tests in their own
+/// process (see [`Isolation`]) are captured by their parent and still run
+/// concurrently.
+///
+/// Except for the tests in their own process, the output is only captured
+/// when the tests are not run from the test harness of `cargo test`
+/// (`harness = false` or a `main` function of your own). The test harness
+/// captures the output of its test functions already and prints it if
+/// they fail, or is given `--nocapture` to not capture it at all.
+///
+/// [`TestResult`]: crate::TestResult
+/// [`TestResult::output`]: crate::TestResult::output
+/// [`Progress`]: crate::Progress
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Capture {
+    /// the output is not captured
+    #[default]
+    Off,
+    /// the output is always attached to the result
+    On,
+    /// the output is attached to the result of the tests that failed
+    OnFailure,
+}
+
+/// where the tests are executed
+///
+/// By default every test runs in a thread of the current process: a test
+/// aborting or overflowing its stack takes all the other tests down with
+/// it and a test ignoring its timeout keeps running in the background.
+/// With [`Isolation::Process`], every [`single!`] test runs in a child
+/// process instead, running the current test binary again and selecting
+/// the test by its path. The process is killed if the test does not
+/// complete in time and crashes are reported as failures (see
+/// [`FailureReason`]).
+///
+/// The setups of the test's groups run again in the child process so
+/// their fixtures are available to the test.
+///
+/// [`single!`]: crate::single
+/// [`FailureReason`]: crate::FailureReason
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Isolation {
+    #[default]
+    Thread,
+    Process,
+}
+
+/// skip the tests when a condition is met
+///
+/// The predicate is evaluated just before every [`single!`] test starts,
+/// the test is reported as [`Outcome::Skipped`] with the reason returned
+/// by the predicate instead of being executed. This adapts the tests to
+/// the machine running them, like skipping the tests needing a database
+/// when its address is not given:
+///
+/// ```
+/// # use scaffolding::{group, setting, settings::SkipIf, single};
+/// let tests = group!(
+///     "database",
+///     [
+///         setting!(SkipIf::env_not_set("DATABASE_URL").or(SkipIf::not_on_path("psql"))),
+///         single!("connects", || true),
+///     ]
+/// );
+/// ```
+///
+/// Like the other settings, a [`SkipIf`] replaces the one of the parent
+/// groups, the conditions are combined with [`SkipIf::or`].
+///
+/// [`single!`]: crate::single
+/// [`Outcome::Skipped`]: crate::Outcome::Skipped
+#[derive(Clone)]
+pub struct SkipIf {
+    predicate: Arc<dyn Fn() -> Option<String> + Send + Sync>,
+}
+
+/// maximum number of tests running at the same time
+///
+/// Only the tests of [`Ordering::Any`] groups are run concurrently, the
+/// tests of a sequence will always wait for the previous one to complete.
+/// Default to the available parallelism of the machine.
+///
+/// [`Ordering::Any`]: crate::Ordering::Any
+#[derive(Debug, Clone, Copy)]
+pub struct Jobs(pub usize);
+
 impl Default for Timeout {
     fn default() -> Self {
         Self(Duration::from_millis(2_345))
@@ -99,3 +277,127 @@ impl Default for StackSize {
     }
 }
 impl Setting for StackSize {}
+
+impl Default for GracePeriod {
+    fn default() -> Self {
+        Self(Duration::from_millis(500))
+    }
+}
+impl Setting for GracePeriod {}
+
+impl Setting for Retry {}
+
+impl Default for Repeat {
+    fn default() -> Self {
+        Self::Times(1)
+    }
+}
+impl Setting for Repeat {}
+
+impl Default for FailFast {
+    fn default() -> Self {
+        Self(
+            std::env::var(FAIL_FAST_ENV)
+                .is_ok_and(|value| !matches!(value.trim(), "" | "0" | "false")),
+        )
+    }
+}
+impl Setting for FailFast {}
+
+impl Default for Backtrace {
+    fn default() -> Self {
+        match std::env::var("RUST_BACKTRACE").as_deref() {
+            Err(_) | Ok("0") => Self::Off,
+            Ok("full") => Self::Full,
+            Ok(_) => Self::Short,
+        }
+    }
+}
+impl Setting for Backtrace {}
+
+impl Setting for Capture {}
+impl Setting for Isolation {}
+
+impl SkipIf {
+    /// skip the tests when the predicate returns a reason
+    pub fn new<F>(predicate: F) -> Self
+    where
+        F: Fn() -> Option<String> + Send + Sync + 'static,
+    {
+        Self {
+            predicate: Arc::new(predicate),
+        }
+    }
+
+    /// skip the tests if the environment variable is not set
+    pub fn env_not_set<N>(name: N) -> Self
+    where
+        N: Into<String>,
+    {
+        let name = name.into();
+        Self::new(move || {
+            std::env::var_os(&name)
+                .is_none()
+                .then(|| format!("environment variable {} not set", name))
+        })
+    }
+
+    /// skip the tests if the binary is not found in any of the
+    /// directories of the `PATH` environment variable
+    pub fn not_on_path<B>(binary: B) -> Self
+    where
+        B: Into<String>,
+    {
+        let binary = binary.into();
+        Self::new(move || {
+            let found = std::env::var_os("PATH").is_some_and(|path| {
+                std::env::split_paths(&path).any(|directory| {
+                    directory.join(&binary).is_file()
+                        || (cfg!(windows) && directory.join(format!("{}.exe", binary)).is_file())
+                })
+            });
+            (!found).then(|| format!("binary `{}` not on PATH", binary))
+        })
+    }
+
+    /// skip the tests if the process is not running as root
+    ///
+    /// the tests are always skipped on the platforms without root user.
+    pub fn not_root() -> Self {
+        Self::new(|| {
+            #[cfg(unix)]
+            let root = unsafe { libc::geteuid() } == 0;
+            #[cfg(not(unix))]
+            let root = false;
+            (!root).then(|| "not running as root".to_owned())
+        })
+    }
+
+    /// skip the tests if either condition is met
+    pub fn or(self, other: SkipIf) -> Self {
+        Self::new(move || (self.predicate)().or_else(|| (other.predicate)()))
+    }
+
+    /// the reason to skip the tests, if they need to be skipped
+    pub(crate) fn reason(&self) -> Option<String> {
+        (self.predicate)()
+    }
+}
+
+impl fmt::Debug for SkipIf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SkipIf").finish_non_exhaustive()
+    }
+}
+impl Setting for SkipIf {}
+
+impl Default for Jobs {
+    fn default() -> Self {
+        Self(
+            thread::available_parallelism()
+                .map(NonZeroUsize::get)
+                .unwrap_or(1),
+        )
+    }
+}
+impl Setting for Jobs {}
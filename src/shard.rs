@@ -0,0 +1,200 @@
+use crate::rng::fnv1a;
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    env, fmt, fs,
+    time::Duration,
+};
+
+/// environment variable selecting the shard to run, see [`Shard::from_env`]
+pub(crate) const SHARD_ENV: &str = "SCAFFOLDING_SHARD";
+/// environment variable giving the file of the recorded durations of the
+/// tests, see [`Shard::from_env`]
+pub(crate) const SHARD_DURATIONS_ENV: &str = "SCAFFOLDING_SHARD_DURATIONS";
+
+/// one slice of the tests, to split a run across several machines
+///
+/// The tests are split in `total` disjoint shards, `index` being the one
+/// to run (from `0` to `total - 1`). The split only depends on the paths
+/// of the tests (and on their recorded durations with
+/// [`Shard::balanced`]) so every machine computes the same partition.
+///
+/// A test and the tests it depends on, or that depend on it, are always
+/// on the same shard: the shard of the first of them in the tree. So are
+/// the tests of the groups executed in sequence or shuffled, whose tests
+/// run after the previous ones (see [`Ordering`]). Only the setups and
+/// teardowns run on every shard executing some of the tests of their
+/// group. The reports of the shards are merged back with
+/// [`Report::merge`].
+///
+/// [`Report::merge`]: crate::Report::merge
+/// [`Ordering`]: crate::Ordering
+#[derive(Debug, Clone)]
+pub struct Shard {
+    pub index: usize,
+    pub total: usize,
+    /// the recorded durations of the tests, by path
+    durations: Option<HashMap<String, Duration>>,
+}
+
+impl Shard {
+    /// split the tests by hashing their path
+    ///
+    /// # panics
+    ///
+    /// if `index` is not less than `total`
+    pub fn new(index: usize, total: usize) -> Self {
+        assert!(
+            index < total,
+            "shard {index} out of {total} does not exist",
+            index = index,
+            total = total
+        );
+        Self {
+            index,
+            total,
+            durations: None,
+        }
+    }
+
+    /// split the tests so the shards take about the same time to run
+    ///
+    /// The tests are assigned from the longest to the shortest to the
+    /// shard with the smallest total duration so far. The tests without
+    /// recorded duration are assumed to take the mean duration of the
+    /// others. The durations are recorded with [`Report::write_durations`].
+    ///
+    /// [`Report::write_durations`]: crate::Report::write_durations
+    ///
+    /// # panics
+    ///
+    /// if `index` is not less than `total`
+    pub fn balanced(index: usize, total: usize, durations: HashMap<String, Duration>) -> Self {
+        Self {
+            durations: Some(durations),
+            ..Self::new(index, total)
+        }
+    }
+
+    /// the shard given to the process, if any
+    ///
+    /// `SCAFFOLDING_SHARD` selects the shard as `index/total` where the
+    /// index starts at `1` (like the matrices of most CI), `2/3` is the
+    /// second of three shards. If `SCAFFOLDING_SHARD_DURATIONS` is the
+    /// path of a file of recorded durations, the shards are balanced.
+    ///
+    /// # panics
+    ///
+    /// if the shard or the durations are not valid
+    pub fn from_env() -> Option<Self> {
+        let shard = env::var(SHARD_ENV).ok()?;
+        let shard = shard.trim();
+        if shard.is_empty() {
+            return None;
+        }
+        let (index, total) = shard
+            .split_once('/')
+            .and_then(|(index, total)| Some((index.parse().ok()?, total.parse().ok()?)))
+            .filter(|&(index, total): &(usize, usize)| index >= 1 && index <= total)
+            .unwrap_or_else(|| {
+                panic!(
+                    "invalid {}: {:?}, expected `index/total` like `1/3`",
+                    SHARD_ENV, shard
+                )
+            });
+
+        Some(match env::var_os(SHARD_DURATIONS_ENV) {
+            Some(path) => {
+                let durations = fs::read_to_string(&path).unwrap_or_else(|error| {
+                    panic!("cannot read {}: {}", SHARD_DURATIONS_ENV, error)
+                });
+                Self::balanced(index - 1, total, Self::parse_durations(&durations))
+            }
+            None => Self::new(index - 1, total),
+        })
+    }
+
+    /// parse the durations written by [`Report::write_durations`]: one test
+    /// per line, its duration in milliseconds followed by its path
+    ///
+    /// [`Report::write_durations`]: crate::Report::write_durations
+    pub fn parse_durations(text: &str) -> HashMap<String, Duration> {
+        text.lines()
+            .filter_map(|line| {
+                let (milliseconds, path) = line.trim().split_once(' ')?;
+                let milliseconds: f64 = milliseconds.parse().ok()?;
+                Some((
+                    path.to_owned(),
+                    Duration::from_secs_f64(milliseconds.max(0.0) / 1_000.0),
+                ))
+            })
+            .collect()
+    }
+
+    /// the paths of this shard among the given paths of the tests
+    ///
+    /// the tests are given in units assigned to the same shard, the first
+    /// path of a unit deciding its shard.
+    pub(crate) fn select<'a>(&self, units: &[Vec<&'a str>]) -> HashSet<&'a str> {
+        match &self.durations {
+            None => units
+                .iter()
+                .filter(|unit| fnv1a(unit[0]) % self.total as u64 == self.index as u64)
+                .flatten()
+                .copied()
+                .collect(),
+            Some(durations) => self.balance(units, durations),
+        }
+    }
+
+    fn balance<'a>(
+        &self,
+        units: &[Vec<&'a str>],
+        durations: &HashMap<String, Duration>,
+    ) -> HashSet<&'a str> {
+        let known: Vec<Duration> = units
+            .iter()
+            .flatten()
+            .filter_map(|path| durations.get(*path).copied())
+            .collect();
+        let mean = if known.is_empty() {
+            Duration::from_millis(1)
+        } else {
+            known.iter().sum::<Duration>() / known.len() as u32
+        };
+
+        let mut units: Vec<(Duration, &Vec<&str>)> = units
+            .iter()
+            .map(|unit| {
+                let duration = unit
+                    .iter()
+                    .map(|path| durations.get(*path).copied().unwrap_or(mean))
+                    .sum();
+                (duration, unit)
+            })
+            .collect();
+        // the path breaks the ties so the order does not depend on the tree
+        units.sort_unstable_by_key(|&(duration, unit)| (Reverse(duration), unit[0]));
+
+        let mut loads = vec![Duration::default(); self.total];
+        let mut selected = HashSet::new();
+        for (duration, unit) in units {
+            let (shard, load) = loads
+                .iter_mut()
+                .enumerate()
+                .min_by_key(|(shard, load)| (**load, *shard))
+                .expect("there is at least one shard");
+            *load += duration;
+            if shard == self.index {
+                selected.extend(unit.iter().copied());
+            }
+        }
+        selected
+    }
+}
+
+impl fmt::Display for Shard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.index + 1, self.total)
+    }
+}
@@ -0,0 +1,178 @@
+use crate::TestName;
+use std::{error::Error, fmt, str::FromStr};
+
+/// environment variable selecting the tests to run by their tags,
+/// see [`TagExpression`]
+pub(crate) const TAGS_ENV: &str = "SCAFFOLDING_TAGS";
+
+/// selects the tests to run by their tags
+///
+/// The tags of a test are its own tags and the tags of its groups (see
+/// [`TestTree::tag`]). The expression is made of tags combined with `&`
+/// (and), `|` (or), `!` (not) and parentheses: `slow & !db` selects the
+/// tests tagged `slow` but not `db`, `(db | network-local) & !slow`
+/// selects the fast tests of the database or of the local network.
+///
+/// [`TestTree::tag`]: crate::TestTree::tag
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagExpression {
+    Tag(TestName),
+    Not(Box<TagExpression>),
+    And(Box<TagExpression>, Box<TagExpression>),
+    Or(Box<TagExpression>, Box<TagExpression>),
+}
+
+/// error in the syntax of a [`TagExpression`]
+#[derive(Debug)]
+pub struct TagExpressionError {
+    expression: String,
+    /// the position of the error in the expression, in bytes
+    position: usize,
+    message: &'static str,
+}
+
+impl TagExpression {
+    /// the expression given to the process with the `SCAFFOLDING_TAGS`
+    /// environment variable, if any
+    ///
+    /// # panics
+    ///
+    /// if the expression is not valid
+    pub fn from_env() -> Option<Self> {
+        let expression = std::env::var(TAGS_ENV).ok()?;
+        if expression.trim().is_empty() {
+            return None;
+        }
+        match expression.parse() {
+            Ok(expression) => Some(expression),
+            Err(error) => panic!("invalid {}: {}", TAGS_ENV, error),
+        }
+    }
+
+    /// returns `true` if the given tags satisfy the expression
+    pub fn matches<T: AsRef<str>>(&self, tags: &[T]) -> bool {
+        match self {
+            Self::Tag(tag) => tags.iter().any(|candidate| candidate.as_ref() == tag),
+            Self::Not(expression) => !expression.matches(tags),
+            Self::And(left, right) => left.matches(tags) && right.matches(tags),
+            Self::Or(left, right) => left.matches(tags) || right.matches(tags),
+        }
+    }
+}
+
+impl FromStr for TagExpression {
+    type Err = TagExpressionError;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            expression,
+            position: 0,
+        };
+        let parsed = parser.or()?;
+        if parser.peek().is_some() {
+            return Err(parser.error("unexpected character"));
+        }
+        Ok(parsed)
+    }
+}
+
+/// recursive descent parser, `!` binds tighter than `&` which binds
+/// tighter than `|`
+struct Parser<'a> {
+    expression: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// the next character that is not a whitespace
+    fn peek(&mut self) -> Option<char> {
+        let rest = &self.expression[self.position..];
+        self.position += rest.len() - rest.trim_start().len();
+        self.expression[self.position..].chars().next()
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.position += expected.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn or(&mut self) -> Result<TagExpression, TagExpressionError> {
+        let mut left = self.and()?;
+        while self.eat('|') {
+            left = TagExpression::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<TagExpression, TagExpressionError> {
+        let mut left = self.not()?;
+        while self.eat('&') {
+            left = TagExpression::And(Box::new(left), Box::new(self.not()?));
+        }
+        Ok(left)
+    }
+
+    fn not(&mut self) -> Result<TagExpression, TagExpressionError> {
+        if self.eat('!') {
+            Ok(TagExpression::Not(Box::new(self.not()?)))
+        } else if self.eat('(') {
+            let expression = self.or()?;
+            if !self.eat(')') {
+                return Err(self.error("expected `)`"));
+            }
+            Ok(expression)
+        } else {
+            self.tag()
+        }
+    }
+
+    fn tag(&mut self) -> Result<TagExpression, TagExpressionError> {
+        self.peek();
+        let rest = &self.expression[self.position..];
+        let length = rest
+            .find(|c: char| c.is_whitespace() || "&|!()".contains(c))
+            .unwrap_or(rest.len());
+        if length == 0 {
+            return Err(self.error("expected a tag"));
+        }
+        self.position += length;
+        Ok(TagExpression::Tag(rest[..length].to_owned().into()))
+    }
+
+    fn error(&self, message: &'static str) -> TagExpressionError {
+        TagExpressionError {
+            expression: self.expression.to_owned(),
+            position: self.position,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for TagExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tag(tag) => write!(f, "{}", tag),
+            Self::Not(expression) => write!(f, "!{}", expression),
+            Self::And(left, right) => write!(f, "({} & {})", left, right),
+            Self::Or(left, right) => write!(f, "({} | {})", left, right),
+        }
+    }
+}
+
+impl fmt::Display for TagExpressionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{message} at position {position} of {expression:?}",
+            message = self.message,
+            position = self.position,
+            expression = self.expression,
+        )
+    }
+}
+
+impl Error for TagExpressionError {}
@@ -0,0 +1,221 @@
+use crate::{
+    reporter::Summary, Ordering, Outcome, Reporter, Terminal, TestName, TestResult, TestedResult,
+};
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+    time::Duration,
+};
+
+/// reports the tests in the Test Anything Protocol (TAP version 14) as
+/// they complete
+///
+/// Every run is a TAP document, the groups are subtests and the setups
+/// and teardowns are test points named `setup <name>` and
+/// `teardown <name>`. The skipped and ignored tests are marked `# SKIP`
+/// with their reason, the todo tests `# TODO`. The failures come with a
+/// YAML diagnostics block giving their reason, details and output.
+///
+/// ```text
+/// TAP version 14
+/// # Subtest: root
+///     ok 1 - passes
+///     not ok 2 - fails
+///       ---
+///       message: "Failure"
+///       severity: fail
+///       reason: failed
+///       duration_ms: 0.012
+///       ...
+///     1..2
+/// not ok 1 - root
+/// 1..1
+/// ```
+pub struct Tap<O> {
+    output: O,
+    /// the root of the document and the groups being reported
+    frames: Vec<Frame>,
+}
+
+#[derive(Default)]
+struct Frame {
+    /// the test points of the frame so far
+    count: usize,
+    failed: bool,
+}
+
+impl Tap<Terminal> {
+    pub fn stdout() -> Self {
+        Self::new_with(Terminal::default())
+    }
+}
+
+impl<O> Tap<O>
+where
+    O: Write,
+{
+    const INDENT: usize = 4;
+
+    pub fn new_with(output: O) -> Self {
+        Self {
+            output,
+            frames: Vec::new(),
+        }
+    }
+
+    /// the indentation of the lines of the current frame
+    fn indent(&self) -> usize {
+        self.frames.len().saturating_sub(1) * Self::INDENT
+    }
+
+    fn frame(&mut self) -> &mut Frame {
+        if self.frames.is_empty() {
+            self.frames.push(Frame::default());
+        }
+        self.frames.last_mut().unwrap()
+    }
+
+    fn point(&mut self, ok: bool, name: &str, directive: Option<String>) -> io::Result<()> {
+        // the failures of the todo tests are expected
+        let todo = directive
+            .as_deref()
+            .is_some_and(|directive| directive.starts_with("TODO"));
+        let frame = self.frame();
+        frame.count += 1;
+        frame.failed |= !ok && !todo;
+        let number = frame.count;
+
+        write!(
+            self.output,
+            "{indent:indent_level$}{status} {number} - {name}",
+            indent = "",
+            indent_level = self.indent(),
+            status = if ok { "ok" } else { "not ok" },
+            number = number,
+            name = escape(name),
+        )?;
+        if let Some(directive) = directive {
+            write!(self.output, " # {}", directive)?;
+        }
+        writeln!(self.output)
+    }
+
+    fn result(&mut self, name: &str, result: &TestResult) -> io::Result<()> {
+        match &result.outcome {
+            Outcome::Success | Outcome::Flaky => self.point(true, name, None),
+            Outcome::Skipped | Outcome::Ignored if result.short == "Todo" => {
+                self.point(false, name, Some(directive("TODO", &result.details)))
+            }
+            Outcome::Skipped | Outcome::Ignored => {
+                self.point(true, name, Some(directive("SKIP", &result.details)))
+            }
+            Outcome::Failure { reason } => {
+                self.point(false, name, None)?;
+                self.diagnostics(reason.name(), result)
+            }
+        }
+    }
+
+    /// the YAML block describing the failure of the last test point
+    fn diagnostics(&mut self, reason: &str, result: &TestResult) -> io::Result<()> {
+        let indent = " ".repeat(self.indent() + 2);
+        let mut yaml = format!("{}---\n", indent);
+        let _ = writeln!(yaml, "{}message: {:?}", indent, result.short.as_ref());
+        let _ = writeln!(yaml, "{}severity: fail", indent);
+        let _ = writeln!(yaml, "{}reason: {}", indent, reason);
+        let _ = writeln!(
+            yaml,
+            "{}duration_ms: {}",
+            indent,
+            milliseconds(result.duration)
+        );
+        for (key, text) in [("details", &result.details), ("output", &result.output)] {
+            let text = text.trim_end();
+            if text.is_empty() {
+                continue;
+            }
+            let _ = writeln!(yaml, "{}{}: |-", indent, key);
+            for line in text.lines() {
+                let _ = writeln!(yaml, "{}  {}", indent, line);
+            }
+        }
+        let _ = writeln!(yaml, "{}...", indent);
+
+        self.output.write_all(yaml.as_bytes())
+    }
+}
+
+impl<O: Write> Reporter for Tap<O> {
+    fn run_start(&mut self) -> io::Result<()> {
+        self.frames = vec![Frame::default()];
+        writeln!(self.output, "TAP version 14")
+    }
+
+    fn group_start(&mut self, name: &TestName, _: &Ordering) -> io::Result<()> {
+        writeln!(
+            self.output,
+            "{indent:indent_level$}# Subtest: {name}",
+            indent = "",
+            indent_level = self.indent(),
+            name = escape(name),
+        )?;
+        self.frames.push(Frame::default());
+        self.output.flush()
+    }
+
+    fn group_end(&mut self, name: &TestName, _: &Ordering) -> io::Result<()> {
+        let indent = self.indent();
+        let frame = self.frames.pop().unwrap_or_default();
+        writeln!(
+            self.output,
+            "{indent:indent_level$}1..{count}",
+            indent = "",
+            indent_level = indent,
+            count = frame.count,
+        )?;
+        self.point(!frame.failed, name, None)?;
+        self.output.flush()
+    }
+
+    fn test_result(&mut self, result: &TestedResult) -> io::Result<()> {
+        match result {
+            TestedResult::Single { name, result, .. } => self.result(name, result)?,
+            TestedResult::Setup { name, result } => {
+                self.result(&format!("setup {}", name), result)?
+            }
+            TestedResult::Teardown { name, result } => {
+                self.result(&format!("teardown {}", name), result)?
+            }
+            TestedResult::GroupStart { .. } | TestedResult::GroupEnd { .. } => (),
+        }
+        self.output.flush()
+    }
+
+    fn run_end(&mut self, _: &Summary) -> io::Result<()> {
+        let count = self.frame().count;
+        self.frames.clear();
+        writeln!(self.output, "1..{}", count)?;
+        self.output.flush()
+    }
+}
+
+/// the `# SKIP` or `# TODO` directive with its reason, if any
+fn directive(kind: &str, reason: &str) -> String {
+    let reason = reason.lines().next().unwrap_or_default().trim();
+    if reason.is_empty() {
+        kind.to_owned()
+    } else {
+        format!("{} {}", kind, escape(reason))
+    }
+}
+
+/// escape the `#` and `\` of a description, they would start a directive
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('#', "\\#")
+        .replace(['\n', '\r'], " ")
+}
+
+fn milliseconds(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64() * 1_000.0)
+}
@@ -1,9 +1,11 @@
 use crate::{
-    settings::{StackSize, Timeout},
-    Outcome, Settings, TestResult,
+    context::Environment,
+    panics, runtime,
+    settings::{Backtrace, GracePeriod, StackSize, Timeout},
+    CancellationToken, Outcome, Settings, TestContext, TestResult,
 };
 use std::{
-    panic::{catch_unwind, set_hook, take_hook, AssertUnwindSafe, PanicInfo},
+    future::Future,
     sync::{Arc, Condvar, Mutex},
     thread,
     time::{Duration, Instant},
@@ -11,6 +13,20 @@ use std::{
 
 pub trait IsTest {
     fn run(self: Box<Self>, settings: Settings) -> TestResult;
+
+    /// run the test and give it back so it can run again
+    ///
+    /// This is how the executor runs a test more than once (see
+    /// [`Retry`]). The default implementation runs the test with
+    /// [`IsTest::run`] and does not give it back: the test only runs once.
+    ///
+    /// [`Retry`]: crate::settings::Retry
+    fn run_and_keep(
+        self: Box<Self>,
+        settings: Settings,
+    ) -> (TestResult, Option<Box<dyn IsTest + Send>>) {
+        (self.run(settings), None)
+    }
 }
 
 impl<R, F> IsTest for F
@@ -18,64 +34,231 @@ where
     F: Fn() -> R + Send + 'static,
     R: Into<TestResult>,
 {
-    #[allow(clippy::mutex_atomic)]
-    #[allow(clippy::unnecessary_unwrap)]
     fn run(self: Box<Self>, settings: Settings) -> TestResult {
-        let timeout = settings.get_or_default::<Timeout>();
-        let duration = Arc::new(Mutex::new(Duration::default()));
-        let update_duration = Arc::clone(&duration);
-        let cond = Arc::new((Mutex::new(false), Condvar::new()));
-        let notify = Arc::clone(&cond);
-
-        let thread = thread::Builder::new().stack_size(settings.get_or_default::<StackSize>().0);
-
-        let backup_instant = Instant::now();
-        let thread = thread
-            .spawn(move || {
-                let instant = Instant::now();
-                set_hook(Box::new(dont_print_panic));
-                let result = catch_unwind(AssertUnwindSafe(|| self().into()));
-                let _ = take_hook();
-                *update_duration.lock().unwrap() = instant.elapsed();
-                let (lock, cvar) = &*notify;
-                let mut finished = lock.lock().unwrap();
-                *finished = true;
-                cvar.notify_one();
-                result
-            })
-            .unwrap();
-        let (lock, cvar) = &*cond;
-        let result = cvar
-            .wait_timeout_while(lock.lock().unwrap(), timeout.0, |pending| !*pending)
-            .unwrap();
-        let duration = *duration.lock().unwrap();
-        let duration = if duration == Duration::default() {
-            backup_instant.elapsed()
+        self.run_and_keep(settings).0
+    }
+
+    fn run_and_keep(
+        self: Box<Self>,
+        settings: Settings,
+    ) -> (TestResult, Option<Box<dyn IsTest + Send>>) {
+        let (result, test) = run_in_thread(settings, self, |test| test().into());
+        (result, test.map(|test| test as Box<dyn IsTest + Send>))
+    }
+}
+
+/// conversion into a boxed [`IsTest`]
+///
+/// This is what the [`single!`], [`setup!`] and [`teardown!`] macros use
+/// to accept the types implementing [`IsTest`] (like the closures
+/// without arguments), the closures taking a [`TestContext`] and the
+/// closures returning a [`Future`] (the async tests). The `Marker` only
+/// exists to tell them apart.
+///
+/// The argument of the closures needs to be annotated
+/// (`|context: &TestContext| ...`) for the compiler to select the
+/// right conversion.
+///
+/// [`single!`]: crate::single
+/// [`setup!`]: crate::setup
+/// [`teardown!`]: crate::teardown
+pub trait IntoTest<Marker> {
+    fn into_test(self) -> Box<dyn IsTest + Send>;
+}
+
+impl<T> IntoTest<()> for T
+where
+    T: IsTest + Send + 'static,
+{
+    fn into_test(self) -> Box<dyn IsTest + Send> {
+        Box::new(self)
+    }
+}
+
+impl<R, F> IntoTest<fn(&TestContext) -> R> for F
+where
+    F: Fn(&TestContext) -> R + Send + 'static,
+    R: Into<TestResult>,
+{
+    fn into_test(self) -> Box<dyn IsTest + Send> {
+        Box::new(WithContext(self))
+    }
+}
+
+impl<F, Fut> IntoTest<fn() -> Fut> for F
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future,
+    Fut::Output: Into<TestResult>,
+{
+    fn into_test(self) -> Box<dyn IsTest + Send> {
+        Box::new(Async(self))
+    }
+}
+
+/// a test taking a [`TestContext`]
+struct WithContext<F>(F);
+
+/// an async test
+///
+/// The future runs in the thread of the test (see `runtime::block_on`),
+/// it is dropped once the test timed out: the test is not leaked as long
+/// as its future yields.
+struct Async<F>(F);
+
+impl<R, F> IsTest for WithContext<F>
+where
+    F: Fn(&TestContext) -> R + Send + 'static,
+    R: Into<TestResult>,
+{
+    fn run(self: Box<Self>, settings: Settings) -> TestResult {
+        self.run_and_keep(settings).0
+    }
+
+    fn run_and_keep(
+        self: Box<Self>,
+        settings: Settings,
+    ) -> (TestResult, Option<Box<dyn IsTest + Send>>) {
+        let context = TestContext::new(&settings);
+        let report = context.report();
+        let (result, test) = run_in_thread(settings, self, move |test| (test.0)(&context).into());
+        let test = test.map(|test| test as Box<dyn IsTest + Send>);
+
+        let logs = report(result.is_failure());
+        let result = if logs.is_empty() {
+            result
+        } else if result.details.is_empty() {
+            TestResult {
+                details: logs.trim_end().to_owned().into(),
+                ..result
+            }
         } else {
-            duration
+            TestResult {
+                details: format!("{}{}", logs, result.details).into(),
+                ..result
+            }
         };
 
-        let result = if result.1.timed_out() {
-            TestResult::timedout(format!(
+        (result, test)
+    }
+}
+
+impl<F, Fut> IsTest for Async<F>
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future,
+    Fut::Output: Into<TestResult>,
+{
+    fn run(self: Box<Self>, settings: Settings) -> TestResult {
+        self.run_and_keep(settings).0
+    }
+
+    fn run_and_keep(
+        self: Box<Self>,
+        settings: Settings,
+    ) -> (TestResult, Option<Box<dyn IsTest + Send>>) {
+        let cancellation = CancellationToken::of(&settings);
+        let (result, test) = run_in_thread(settings, self, move |test| {
+            runtime::block_on((test.0)(), &cancellation)
+                .map_or_else(|| TestResult::timedout("Test cancelled"), Into::into)
+        });
+        (result, test.map(|test| test as Box<dyn IsTest + Send>))
+    }
+}
+
+/// run the test in its own thread, with the [`StackSize`] and the
+/// [`Timeout`] of the settings
+///
+/// the test's cancellation token is cancelled if it timed out, the test
+/// is reported as leaked if it is still running after the [`GracePeriod`].
+///
+/// the test is given back if it completed in time.
+#[allow(clippy::mutex_atomic)]
+#[allow(clippy::unnecessary_unwrap)]
+fn run_in_thread<T, F>(settings: Settings, test: T, run: F) -> (TestResult, Option<T>)
+where
+    T: Send + 'static,
+    F: FnOnce(&T) -> TestResult + Send + 'static,
+{
+    let timeout = settings.get_or_default::<Timeout>();
+    let backtrace = *settings.get_or_default::<Backtrace>();
+    let duration = Arc::new(Mutex::new(Duration::default()));
+    let update_duration = Arc::clone(&duration);
+    let cond = Arc::new((Mutex::new(false), Condvar::new()));
+    let notify = Arc::clone(&cond);
+
+    let thread = thread::Builder::new().stack_size(settings.get_or_default::<StackSize>().0);
+
+    let backup_instant = Instant::now();
+    let thread = thread
+        .spawn(move || {
+            let instant = Instant::now();
+            let result = panics::catch(backtrace, || run(&test));
+            *update_duration.lock().unwrap() = instant.elapsed();
+            let (lock, cvar) = &*notify;
+            let mut finished = lock.lock().unwrap();
+            *finished = true;
+            cvar.notify_one();
+            (result, test)
+        })
+        .unwrap();
+    let (lock, cvar) = &*cond;
+    let result = cvar
+        .wait_timeout_while(lock.lock().unwrap(), timeout.0, |pending| !*pending)
+        .unwrap();
+    let duration = *duration.lock().unwrap();
+    let duration = if duration == Duration::default() {
+        backup_instant.elapsed()
+    } else {
+        duration
+    };
+
+    let (result, test) = if result.1.timed_out() {
+        if let Some(environment) = settings.get::<Environment>() {
+            environment.cancellation.cancel();
+        }
+        let grace_period = settings.get_or_default::<GracePeriod>();
+        let (finished, wait) = cvar
+            .wait_timeout_while(result.0, grace_period.0, |pending| !*pending)
+            .unwrap();
+        // the thread needs the lock to complete
+        std::mem::drop(finished);
+        if wait.timed_out() {
+            let result = TestResult::leaked(format!(
+                "Test timedout, allocated duration was {:.2?}, and was still running {:.2?} after being cancelled",
+                timeout.0, grace_period.0
+            ));
+            (result, None)
+        } else {
+            let result = TestResult::timedout(format!(
                 "Test timedout, allocated duration was {:.2?}",
                 timeout.0
-            ))
-        } else {
-            let result = thread.join();
-
-            match result {
-                Err(_error) | Ok(Err(_error)) => TestResult {
+            ));
+            (result, thread.join().ok().map(|(_, test)| test))
+        }
+    } else {
+        match thread.join() {
+            Ok((Ok(result), test)) => (result, Some(test)),
+            Ok((Err(details), test)) => (
+                TestResult {
+                    outcome: Outcome::Failure {
+                        reason: crate::FailureReason::Panicked,
+                    },
+                    ..TestResult::failed(details)
+                },
+                Some(test),
+            ),
+            Err(_error) => (
+                TestResult {
                     outcome: Outcome::Failure {
                         reason: crate::FailureReason::Panicked,
                     },
                     ..TestResult::failed("test panicked")
                 },
-                Ok(Ok(result)) => result,
-            }
-        };
+                None,
+            ),
+        }
+    };
 
-        TestResult { duration, ..result }
-    }
+    (TestResult { duration, ..result }, test)
 }
-
-fn dont_print_panic(_: &PanicInfo) {}
@@ -1,18 +1,55 @@
-use crate::{report::Progress, settings::Setting, Executor, IsTest, Ordering, Report, TestName};
+use crate::{
+    expect::{Expect, Expectation},
+    reporter,
+    settings::Setting,
+    Executor, Ignored, IsTest, Ordering, Report, Reporter, TestName,
+};
 use std::{collections::LinkedList, sync::Arc};
 
+/// separator between the names of the groups and of the test
+/// in the full path of a test
+pub(crate) const PATH_SEPARATOR: &str = "::";
+
+/// the path of a test or group named `name` in the group of path `group`,
+/// the root group having an empty path
+pub(crate) fn join(group: &str, name: &str) -> String {
+    if group.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{}{}{}", group, PATH_SEPARATOR, name)
+    }
+}
+
 pub enum TestTree {
     Single {
         name: TestName,
         test: Box<dyn IsTest + Send>,
+        dependencies: Vec<TestName>,
+        tags: Vec<TestName>,
+        ignored: Option<Ignored>,
     },
     SetSetting {
         value: Arc<dyn Setting>,
     },
+    /// executed before all the other tests of the group
+    ///
+    /// if the setup fails, all the tests of the group are skipped
+    Setup {
+        name: TestName,
+        test: Box<dyn IsTest + Send>,
+    },
+    /// executed after all the other tests of the group, even if they failed
+    Teardown {
+        name: TestName,
+        test: Box<dyn IsTest + Send>,
+    },
     Group {
         name: TestName,
         ordering: Ordering,
         tests: Vec<TestTree>,
+        dependencies: Vec<TestName>,
+        tags: Vec<TestName>,
+        ignored: Option<Ignored>,
     },
 }
 
@@ -20,13 +57,27 @@ pub enum TestItem {
     Single {
         name: TestName,
         test: Box<dyn IsTest + Send>,
+        dependencies: Vec<TestName>,
+        tags: Vec<TestName>,
+        ignored: Option<Ignored>,
     },
     SetSetting {
         value: Arc<dyn Setting>,
     },
+    Setup {
+        name: TestName,
+        test: Box<dyn IsTest + Send>,
+    },
+    Teardown {
+        name: TestName,
+        test: Box<dyn IsTest + Send>,
+    },
     GroupStart {
         name: TestName,
         ordering: Ordering,
+        dependencies: Vec<TestName>,
+        tags: Vec<TestName>,
+        ignored: Option<Ignored>,
     },
     GroupEnd {
         name: TestName,
@@ -51,18 +102,187 @@ impl TestTree {
         TreeIterator { set }
     }
 
+    /// declare the tests of this tree may only run once the given
+    /// test, or all the tests of the given group, succeeded.
+    ///
+    /// The dependency is the path of the test, the names of its groups
+    /// and its own name separated with `::`. Only the end of the path is
+    /// necessary as long as it matches only one test or group: `"b::c"`
+    /// will match `"a::b::c"`. Dependencies do not need to be declared
+    /// before the test, they are executed first.
+    ///
+    /// If the dependency fails or is skipped, the tests are skipped.
+    ///
+    /// # panics
+    ///
+    /// only tests and groups can have dependencies, this function will panic
+    /// if called on a [`TestTree::SetSetting`], a [`TestTree::Setup`] or
+    /// a [`TestTree::Teardown`]. Declare the dependency on the group instead.
+    pub fn depends_on<D>(mut self, dependency: D) -> Self
+    where
+        D: Into<TestName>,
+    {
+        match &mut self {
+            Self::Single { dependencies, .. } | Self::Group { dependencies, .. } => {
+                dependencies.push(dependency.into())
+            }
+            Self::SetSetting { .. } => panic!("a setting cannot depend on a test"),
+            Self::Setup { .. } | Self::Teardown { .. } => {
+                panic!("setup and teardown cannot depend on a test, use the group instead")
+            }
+        }
+        self
+    }
+
+    /// tag the test, or all the tests of the group
+    ///
+    /// Tags like `slow`, `db` or the team owning the tests are reported
+    /// with the results and select the tests to run with a
+    /// [`TagExpression`]. The tests inherit the tags of their groups.
+    ///
+    /// # panics
+    ///
+    /// only tests and groups can be tagged, this function will panic
+    /// if called on a [`TestTree::SetSetting`], a [`TestTree::Setup`] or
+    /// a [`TestTree::Teardown`].
+    ///
+    /// [`TagExpression`]: crate::TagExpression
+    pub fn tag<T>(mut self, tag: T) -> Self
+    where
+        T: Into<TestName>,
+    {
+        match &mut self {
+            Self::Single { tags, .. } | Self::Group { tags, .. } => {
+                let tag = tag.into();
+                if !tags.contains(&tag) {
+                    tags.push(tag)
+                }
+            }
+            Self::SetSetting { .. } => panic!("a setting cannot be tagged"),
+            Self::Setup { .. } | Self::Teardown { .. } => {
+                panic!("setup and teardown cannot be tagged, tag the group instead")
+            }
+        }
+        self
+    }
+
+    /// do not execute the test, or the tests of the group, for the given
+    /// reason
+    ///
+    /// The test is still reported, with the reason. The ignored tests can
+    /// be executed anyway, see [`RunIgnored`].
+    ///
+    /// # panics
+    ///
+    /// only tests and groups can be ignored, this function will panic
+    /// if called on a [`TestTree::SetSetting`], a [`TestTree::Setup`] or
+    /// a [`TestTree::Teardown`].
+    ///
+    /// [`RunIgnored`]: crate::RunIgnored
+    pub fn ignore<R>(self, reason: R) -> Self
+    where
+        R: Into<TestName>,
+    {
+        self.ignored(Ignored::Reason(reason.into()))
+    }
+
+    /// mark the test, or the tests of the group, as not implemented yet
+    ///
+    /// The test is reported but never executed, see [`todo_test!`] to
+    /// declare a test without implementing it.
+    ///
+    /// # panics
+    ///
+    /// like [`TestTree::ignore`], only tests and groups can be todo.
+    ///
+    /// [`todo_test!`]: crate::todo_test
+    pub fn todo(self) -> Self {
+        self.ignored(Ignored::Todo)
+    }
+
+    /// expect the test to fail
+    ///
+    /// The test failing as expected is reported as a success labelled
+    /// `XFail`, the test succeeding is reported as a failure labelled
+    /// `XPass` (see [`FailureReason::UnexpectedPass`]).
+    ///
+    /// # panics
+    ///
+    /// only a [`TestTree::Single`] can be expected to fail.
+    ///
+    /// [`FailureReason::UnexpectedPass`]: crate::FailureReason::UnexpectedPass
+    pub fn should_fail(self) -> Self {
+        self.expect(Expectation::Failure)
+    }
+
+    /// expect the test to panic
+    ///
+    /// Like [`TestTree::should_fail`] but only a panic is expected, with a
+    /// message containing `expected` if it is given. `expected` is a glob
+    /// matching the whole message if it contains a `*` or a `?`.
+    ///
+    /// # panics
+    ///
+    /// only a [`TestTree::Single`] can be expected to panic.
+    pub fn should_panic(self, expected: Option<&str>) -> Self {
+        self.expect(Expectation::Panic(expected.map(str::to_owned)))
+    }
+
+    fn expect(mut self, expectation: Expectation) -> Self {
+        match &mut self {
+            Self::Single { test, .. } => {
+                let inner = std::mem::replace(test, Box::new(|| ()));
+                *test = Box::new(Expect {
+                    test: inner,
+                    expectation,
+                });
+            }
+            _ => panic!("only a single test can be expected to fail"),
+        }
+        self
+    }
+
+    fn ignored(mut self, why: Ignored) -> Self {
+        match &mut self {
+            Self::Single { ignored, .. } | Self::Group { ignored, .. } => *ignored = Some(why),
+            Self::SetSetting { .. } => panic!("a setting cannot be ignored"),
+            Self::Setup { .. } | Self::Teardown { .. } => {
+                panic!("setup and teardown cannot be ignored, ignore the group instead")
+            }
+        }
+        self
+    }
+
     /// handy function to compile a report from the given [`TestTree`]
     ///
-    /// This function will block until all the tests are executed.
+    /// This function will block until all the tests are executed. The
+    /// progress is reported by the reporters of `SCAFFOLDING_REPORTER`
+    /// or on the standard output (see [`TestTree::run_with`]).
     ///
     #[inline]
     pub fn run(self) -> Report {
+        self.run_with(&mut reporter::from_env_or_progress())
+    }
+
+    /// compile a report from the given [`TestTree`], sending the events
+    /// of the run to the given reporter
+    ///
+    /// This function will block until all the tests are executed. They are
+    /// executed as configured by the environment, see
+    /// [`Executor::from_env`].
+    ///
+    /// # panics
+    ///
+    /// if the reporter fails to report the run
+    pub fn run_with<R>(self, reporter: &mut R) -> Report
+    where
+        R: Reporter + ?Sized,
+    {
         let mut reports = vec![];
-        let mut progress = Progress::stdout();
-        for result in Executor::new(self) {
-            progress.handle(&result).unwrap();
-            reports.push(result);
-        }
+        reporter::run(Executor::from_env(self), reporter, |result| {
+            reports.push(result)
+        })
+        .unwrap_or_else(|error| panic!("Failed to report test's result: {}", error));
 
         reports.into_iter().collect()
     }
@@ -73,12 +293,29 @@ impl Iterator for TreeIterator {
     fn next(&mut self) -> Option<Self::Item> {
         match self.set.pop_front()? {
             Either::B(item) => Some(item),
-            Either::A(TestTree::Single { name, test }) => Some(TestItem::Single { name, test }),
+            Either::A(TestTree::Single {
+                name,
+                test,
+                dependencies,
+                tags,
+                ignored,
+            }) => Some(TestItem::Single {
+                name,
+                test,
+                dependencies,
+                tags,
+                ignored,
+            }),
             Either::A(TestTree::SetSetting { value }) => Some(TestItem::SetSetting { value }),
+            Either::A(TestTree::Setup { name, test }) => Some(TestItem::Setup { name, test }),
+            Either::A(TestTree::Teardown { name, test }) => Some(TestItem::Teardown { name, test }),
             Either::A(TestTree::Group {
                 name,
                 ordering,
                 tests,
+                dependencies,
+                tags,
+                ignored,
             }) => {
                 self.set.push_front(Either::B(TestItem::GroupEnd {
                     name: name.clone(),
@@ -89,7 +326,13 @@ impl Iterator for TreeIterator {
                 tests.append(&mut self.set);
                 self.set = tests;
 
-                Some(TestItem::GroupStart { name, ordering })
+                Some(TestItem::GroupStart {
+                    name,
+                    ordering,
+                    dependencies,
+                    tags,
+                    ignored,
+                })
             }
         }
     }
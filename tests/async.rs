@@ -0,0 +1,92 @@
+use scaffolding::{
+    group, setting,
+    settings::{GracePeriod, Timeout},
+    single, Executor, TestedResult,
+};
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// yields once before completing, like most futures waiting on something
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+/// never completes
+struct Pending;
+
+impl Future for Pending {
+    type Output = bool;
+
+    fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<bool> {
+        Poll::Pending
+    }
+}
+
+#[test]
+fn async_tests() {
+    let tests = group!(
+        "async",
+        [
+            setting!(Timeout(Duration::from_millis(100))),
+            setting!(GracePeriod(Duration::from_millis(500))),
+            single!("passes", || async {
+                YieldNow(false).await;
+                true
+            }),
+            single!("fails", || async { false }),
+            single!("returns an error", || async {
+                YieldNow(false).await;
+                Err::<(), _>("not found")
+            }),
+            single!("never completes", || Pending),
+            #[cfg(feature = "with-tokio")]
+            single!("uses tokio", || async {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+                true
+            }),
+        ]
+    );
+
+    let results: Vec<(String, String, String)> = Executor::new(tests)
+        .filter_map(|result| match result {
+            TestedResult::Single { name, result, .. } => Some((
+                name.into_owned(),
+                result.short.into_owned(),
+                result.details.into_owned(),
+            )),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(results[0], ("passes".into(), "Success".into(), "".into()));
+    assert_eq!(results[1], ("fails".into(), "Failure".into(), "".into()));
+    assert_eq!(
+        results[2],
+        (
+            "returns an error".into(),
+            "Failure".into(),
+            "not found".into()
+        )
+    );
+    // the future is dropped once cancelled, the test does not leak
+    assert_eq!(results[3].1, "Timeout", "{:?}", results[3]);
+    if cfg!(feature = "with-tokio") {
+        assert_eq!(results[4].1, "Success", "{:?}", results[4]);
+    }
+}
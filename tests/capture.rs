@@ -0,0 +1,103 @@
+//! the output is captured by redirecting the standard output and error of
+//! the whole process, which is never done from the threads of the test
+//! harness: these tests run one after the other from `main`
+
+mod common;
+
+use common::results;
+use scaffolding::{
+    group, setting,
+    settings::{Capture, Jobs},
+    single, Executor,
+};
+use std::{thread, time::Duration};
+
+fn output_is_captured() {
+    let tests = group!(
+        "root",
+        [
+            single!("not captured by default", || println!("default output")),
+            setting!(Capture::OnFailure),
+            single!("passes", || println!("passing output")),
+            single!("fails", || {
+                println!("failing output");
+                false
+            }),
+            setting!(Capture::On),
+            single!("always", || println!("kept output")),
+            setting!(Capture::Off),
+            single!("off", || {
+                println!("not captured");
+                false
+            }),
+        ]
+    );
+
+    let results = results(Executor::new(tests));
+
+    assert_eq!(results["not captured by default"].output, "");
+    assert_eq!(results["passes"].output, "");
+    assert_eq!(results["fails"].output, "failing output\n");
+    assert_eq!(results["always"].output, "kept output\n");
+    assert_eq!(results["off"].output, "");
+}
+
+fn output_of_concurrent_tests_is_not_mixed() {
+    let printer = |name: &'static str| {
+        single!(name, move || {
+            for _ in 0..3 {
+                println!("{}", name);
+                thread::sleep(Duration::from_millis(10));
+            }
+        })
+    };
+    let tests = group!(
+        "root",
+        [
+            setting!(Jobs(4)),
+            setting!(Capture::On),
+            printer("a"),
+            printer("b"),
+            printer("c"),
+            printer("d"),
+        ]
+    );
+
+    let results = results(Executor::new(tests));
+
+    for name in ["a", "b", "c", "d"] {
+        assert_eq!(results[name].output, format!("{0}\n{0}\n{0}\n", name));
+    }
+}
+
+fn output_of_concurrent_runs_is_not_mixed() {
+    let run = |name: &'static str| {
+        thread::spawn(move || {
+            let tests = group!(
+                "root",
+                [
+                    setting!(Capture::On),
+                    single!(name, move || {
+                        for _ in 0..3 {
+                            println!("{}", name);
+                            thread::sleep(Duration::from_millis(10));
+                        }
+                    }),
+                ]
+            );
+            results(Executor::new(tests))
+        })
+    };
+
+    let runs = vec![run("a"), run("b")];
+    for (run, name) in runs.into_iter().zip(vec!["a", "b"]) {
+        let results = run.join().unwrap();
+        assert_eq!(results[name].output, format!("{0}\n{0}\n{0}\n", name));
+    }
+}
+
+fn main() {
+    output_is_captured();
+    output_of_concurrent_tests_is_not_mixed();
+    output_of_concurrent_runs_is_not_mixed();
+}
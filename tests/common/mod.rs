@@ -0,0 +1,12 @@
+use scaffolding::{Executor, TestResult, TestedResult};
+use std::collections::HashMap;
+
+/// the results of the tests of the run, by name
+pub fn results(executor: Executor) -> HashMap<String, TestResult> {
+    executor
+        .filter_map(|result| match result {
+            TestedResult::Single { name, result, .. } => Some((name.into_owned(), result)),
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use common::results;
+use scaffolding::{
+    group, setting,
+    settings::{GracePeriod, Timeout},
+    single, Executor, TestContext,
+};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::sleep,
+    time::Duration,
+};
+
+#[test]
+fn context_of_the_test() {
+    let tests = group!(
+        "root",
+        [group!(
+            "nested",
+            [
+                setting!(Timeout(Duration::from_secs(1))),
+                single!("path", |context: &TestContext| {
+                    context.path() == "root::nested::path"
+                }),
+                single!("settings", |context: &TestContext| {
+                    context.settings().get_or_default::<Timeout>().0 == Duration::from_secs(1)
+                }),
+                single!("rng", |context: &TestContext| {
+                    context.rng().next_u64() == context.rng().next_u64()
+                }),
+                single!("logs", |context: &TestContext| {
+                    context.log("first line");
+                    context.log(2);
+                    false
+                }),
+            ]
+        )]
+    );
+
+    for (name, result) in results(Executor::new(tests)) {
+        if name == "logs" {
+            assert!(result.is_failure());
+            assert!(result.details.starts_with("first line\n2"));
+        } else {
+            assert!(result.is_success(), "{}", name);
+        }
+    }
+}
+
+#[test]
+fn cancelled_on_timeout() {
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let observed = Arc::clone(&cancelled);
+    let tests = group!(
+        "root",
+        [
+            setting!(Timeout(Duration::from_millis(50))),
+            single!("loops", move |context: &TestContext| {
+                context.cancellation().wait();
+                observed.store(true, Ordering::SeqCst);
+            }),
+        ]
+    );
+
+    let results = results(Executor::new(tests));
+    assert!(results["loops"].is_timeout());
+    assert!(cancelled.load(Ordering::SeqCst));
+}
+
+#[test]
+fn leaked_after_grace_period() {
+    let tests = group!(
+        "root",
+        [
+            setting!(Timeout(Duration::from_millis(50))),
+            setting!(GracePeriod(Duration::from_millis(50))),
+            single!("ignores the cancellation", || sleep(Duration::from_secs(1))),
+            single!("polls the cancellation", |context: &TestContext| {
+                while !context
+                    .cancellation()
+                    .wait_timeout(Duration::from_millis(10))
+                {}
+            }),
+        ]
+    );
+
+    let results = results(Executor::new(tests));
+    assert!(results["ignores the cancellation"].is_leaked());
+    assert!(results["polls the cancellation"].is_timeout());
+}
@@ -0,0 +1,102 @@
+use scaffolding::{
+    group, single, DependencyError, Executor, Ordering, OrderingCondition, TestedResult,
+};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn dependencies_run_first() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let (first, second) = (Arc::clone(&log), Arc::clone(&log));
+    let tests = group!(
+        "root",
+        [
+            group!(
+                "a",
+                [
+                    single!("dependent", move || first.lock().unwrap().push("dependent"))
+                        .depends_on("b::dependency")
+                ]
+            ),
+            group!(
+                "b",
+                [single!("dependency", move || second
+                    .lock()
+                    .unwrap()
+                    .push("dependency"))]
+            ),
+        ]
+    );
+
+    for result in Executor::new(tests) {
+        if let TestedResult::Single { result, .. } = result {
+            assert!(result.is_success());
+        }
+    }
+
+    assert_eq!(*log.lock().unwrap(), vec!["dependency", "dependent"]);
+}
+
+#[test]
+fn failed_dependency_skips() {
+    let tests = group!(
+        "root",
+        [
+            single!("dependent", || true).depends_on("failing"),
+            single!("failing", || false),
+        ]
+    );
+
+    let results: Vec<_> = Executor::new(tests)
+        .filter_map(|result| match result {
+            TestedResult::Single { result, .. } => Some(result),
+            _ => None,
+        })
+        .collect();
+
+    assert!(results[0].is_skipped());
+    assert_eq!(results[0].details, "dependency root::failing failed");
+    assert!(results[1].is_failure());
+}
+
+#[test]
+fn unknown_dependency() {
+    let tests = group!("root", [single!("test", || true).depends_on("missing")]);
+
+    assert!(matches!(
+        Executor::try_new(tests),
+        Err(DependencyError::Unknown { .. })
+    ));
+}
+
+#[test]
+fn cyclic_dependencies() {
+    let tests = group!(
+        "root",
+        [
+            single!("a", || true).depends_on("b"),
+            single!("b", || true).depends_on("a"),
+        ]
+    );
+
+    assert!(matches!(
+        Executor::try_new(tests),
+        Err(DependencyError::Cycle { .. })
+    ));
+}
+
+#[test]
+fn dependency_against_sequence() {
+    // the sequence requires "a" to complete before "b" starts
+    let tests = group!(
+        "root",
+        Ordering::Sequence {
+            on_condition: OrderingCondition::Finish
+        },
+        [single!("a", || true).depends_on("b"), single!("b", || true),]
+    );
+
+    assert!(matches!(
+        Executor::try_new(tests),
+        Err(DependencyError::Cycle { .. })
+    ));
+}
@@ -0,0 +1,194 @@
+use scaffolding::{
+    group, setting,
+    settings::{Jobs, Timeout},
+    single, Executor, Ordering, OrderingCondition, Report, TestTree, TestedResult,
+};
+use std::{
+    sync::{Arc, Barrier, Mutex},
+    thread::sleep,
+    time::Duration,
+};
+
+fn sleepy(log: &Arc<Mutex<Vec<usize>>>, id: usize) -> TestTree {
+    let log = Arc::clone(log);
+    single!(format!("sleepy {}", id), move || {
+        sleep(Duration::from_millis(200));
+        log.lock().unwrap().push(id);
+    })
+}
+
+#[test]
+fn any_runs_concurrently() {
+    // every test waits for the others, they only complete if the four of
+    // them run at the same time
+    let barrier = Arc::new(Barrier::new(4));
+    let waiting = |id: usize| {
+        let barrier = Arc::clone(&barrier);
+        single!(format!("waiting {}", id), move || {
+            barrier.wait();
+        })
+    };
+    let tests = group!(
+        "any",
+        [
+            setting!(Jobs(4)),
+            waiting(0),
+            waiting(1),
+            waiting(2),
+            waiting(3),
+        ]
+    );
+
+    let report: Report = Executor::new(tests).collect();
+    assert!(report.is_success(), "{:?}", report);
+}
+
+#[test]
+fn sequence_keeps_ordering() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let tests = group!(
+        "sequence",
+        Ordering::Sequence {
+            on_condition: OrderingCondition::Finish
+        },
+        [
+            setting!(Jobs(4)),
+            sleepy(&log, 0),
+            group!("nested any", [sleepy(&log, 1), sleepy(&log, 2)]),
+            sleepy(&log, 3),
+        ]
+    );
+
+    let report: Report = Executor::new(tests).collect();
+
+    assert!(report.is_success());
+    let log = log.lock().unwrap();
+    assert_eq!(log[0], 0);
+    assert_eq!(log[3], 3);
+}
+
+#[test]
+fn sequence_skips_after_failure() {
+    let tests = group!(
+        "sequence",
+        Ordering::Sequence {
+            on_condition: OrderingCondition::Success
+        },
+        [
+            single!("first", || true),
+            single!("second", || false),
+            single!("third", || true),
+        ]
+    );
+
+    let report: Report = Executor::new(tests).collect();
+
+    if let Report::Group { reports, .. } = report {
+        let skipped: Vec<bool> = reports
+            .iter()
+            .map(|report| match report {
+                Report::Test { result, .. } => result.is_skipped(),
+                _ => false,
+            })
+            .collect();
+        assert_eq!(skipped, vec![false, false, true]);
+    } else {
+        panic!("expected a group report")
+    }
+}
+
+fn shuffled(seed: u64) -> Vec<String> {
+    let tests = group!(
+        "shuffled",
+        Ordering::Shuffled { seed: Some(seed) },
+        [
+            single!("a", || true),
+            single!("b", || true),
+            single!("c", || true),
+            single!("d", || true),
+            single!("e", || true),
+            single!("f", || true),
+        ]
+    );
+
+    Executor::new(tests)
+        .filter_map(|result| match result {
+            TestedResult::Single { name, .. } => Some(name.into_owned()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn shuffled_is_reproducible() {
+    assert_eq!(shuffled(42), shuffled(42));
+
+    let mut order = shuffled(42);
+    order.sort();
+    assert_eq!(order, vec!["a", "b", "c", "d", "e", "f"]);
+}
+
+/// the seeds reported by the groups and the order of the tests
+fn shuffled_without_seed() -> (Vec<u64>, Vec<String>) {
+    let shuffled = |name: &'static str| {
+        group!(
+            name,
+            Ordering::Shuffled { seed: None },
+            [
+                single!("a", || true),
+                single!("b", || true),
+                single!("c", || true),
+                single!("d", || true),
+                single!("e", || true),
+                single!("f", || true),
+            ]
+        )
+    };
+    let tests = group!("root", [shuffled("x"), shuffled("y"), shuffled("z")]);
+
+    let mut seeds = Vec::new();
+    let mut order = Vec::new();
+    for result in Executor::from_env(tests) {
+        match result {
+            TestedResult::GroupStart {
+                ordering: Ordering::Shuffled { seed: Some(seed) },
+                ..
+            } => seeds.push(seed),
+            TestedResult::Single { name, .. } => order.push(name.into_owned()),
+            _ => (),
+        }
+    }
+    (seeds, order)
+}
+
+#[test]
+fn shuffled_groups_share_the_seed_of_the_run() {
+    let (seeds, order) = shuffled_without_seed();
+    assert_eq!(seeds.len(), 3);
+    assert!(seeds.iter().all(|seed| *seed == seeds[0]), "{:?}", seeds);
+
+    std::env::set_var("SCAFFOLDING_SEED", seeds[0].to_string());
+    let replayed = shuffled_without_seed();
+    std::env::remove_var("SCAFFOLDING_SEED");
+    assert_eq!(replayed, (seeds, order));
+}
+
+#[test]
+fn shuffled_keeps_settings() {
+    let tests = group!(
+        "shuffled",
+        Ordering::Shuffled { seed: Some(7) },
+        [
+            single!("default", || true),
+            setting!(Timeout(Duration::from_millis(50))),
+            single!("short", || sleep(Duration::from_millis(200))),
+            single!("short too", || sleep(Duration::from_millis(200))),
+        ]
+    );
+
+    for result in Executor::new(tests) {
+        if let TestedResult::Single { name, result, .. } = result {
+            assert_eq!(result.is_timeout(), name != "default", "{}", name);
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use scaffolding::{group, single, Executor, FailureReason, Outcome, TestContext, TestedResult};
+
+#[test]
+fn expected_failures() {
+    let tests = group!(
+        "root",
+        [
+            single!("fails", || false).should_fail(),
+            single!("passes", || true).should_fail(),
+            single!("panics", || -> bool { panic!("index out of bounds: 3") }).should_panic(None),
+            single!("matching message", || -> bool {
+                panic!("index out of bounds: 3")
+            })
+            .should_panic(Some("out of bounds")),
+            single!("matching glob", |context: &TestContext| -> bool {
+                context.log("some log");
+                panic!("index out of bounds: 3")
+            })
+            .should_panic(Some("index * 3")),
+            single!("other message", || -> bool { panic!("division by zero") })
+                .should_panic(Some("out of bounds")),
+            single!("no panic", || false).should_panic(None),
+            single!("normal", || true),
+        ]
+    );
+
+    let results: Vec<(String, String)> = Executor::new(tests)
+        .filter_map(|result| match result {
+            TestedResult::Single { name, result, .. } => {
+                if result.short == "XPass" {
+                    assert!(matches!(
+                        result.outcome,
+                        Outcome::Failure {
+                            reason: FailureReason::UnexpectedPass
+                        }
+                    ));
+                }
+                Some((name.into_owned(), result.short.into_owned()))
+            }
+            _ => None,
+        })
+        .collect();
+
+    let expected = [
+        ("fails", "XFail"),
+        ("passes", "XPass"),
+        ("panics", "XFail"),
+        ("matching message", "XFail"),
+        ("matching glob", "XFail"),
+        ("other message", "Failure"),
+        ("no panic", "Failure"),
+        ("normal", "Success"),
+    ];
+    let expected: Vec<(String, String)> = expected
+        .iter()
+        .map(|(name, short)| ((*name).to_owned(), (*short).to_owned()))
+        .collect();
+    assert_eq!(results, expected);
+}
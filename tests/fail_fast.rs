@@ -0,0 +1,54 @@
+use scaffolding::{
+    group, setting, settings::FailFast, single, teardown, Executor, Ordering, TestedResult,
+};
+
+#[test]
+fn stops_after_the_first_failure() {
+    let tests = group!(
+        "root",
+        [
+            setting!(FailFast(true)),
+            group!(
+                "first",
+                [
+                    single!("passing", || true),
+                    single!("failing", || false),
+                    teardown!("cleanup", || true),
+                ]
+            ),
+            group!(
+                "second",
+                Ordering::Any,
+                [single!("not run", || true), single!("neither", || true)]
+            ),
+        ]
+    );
+
+    let events: Vec<String> = Executor::new(tests)
+        .map(|result| match result {
+            TestedResult::Single { name, result, .. } | TestedResult::Teardown { name, result } => {
+                format!("{}: {} {}", name, result.short, result.details)
+            }
+            TestedResult::Setup { name, .. } => format!("setup {}", name),
+            TestedResult::GroupStart { name, .. } => format!("start {}", name),
+            TestedResult::GroupEnd { name, .. } => format!("end {}", name),
+        })
+        .collect();
+
+    assert_eq!(
+        events,
+        vec![
+            "start root",
+            "start first",
+            "passing: Success ",
+            "failing: Failure ",
+            "cleanup: Success ",
+            "end first",
+            "start second",
+            "not run: Skipped not run: fail-fast triggered by root::first::failing",
+            "neither: Skipped not run: fail-fast triggered by root::first::failing",
+            "end second",
+            "end root",
+        ]
+    );
+}
@@ -0,0 +1,134 @@
+use scaffolding::{
+    group, setup, single, teardown, Executor, Filter, Ordering, TestTree, TestedResult,
+};
+
+fn tests() -> TestTree {
+    group!(
+        "root",
+        Ordering::Sequence {
+            on_condition: scaffolding::OrderingCondition::Success
+        },
+        [
+            group!(
+                "login",
+                [
+                    setup!("connect", || true),
+                    single!("valid password", || true),
+                    single!("wrong password", || true),
+                    teardown!("disconnect", || true),
+                ]
+            ),
+            group!(
+                "search",
+                [
+                    setup!("index", || true),
+                    single!("by name", || true),
+                    single!("by date", || true).depends_on("root::login::valid password"),
+                ]
+            ),
+            single!("logout", || true),
+        ]
+    )
+}
+
+fn events(filter: &str) -> Vec<String> {
+    Executor::new(tests())
+        .filter(&Filter::new(filter.split(',')))
+        .map(|result| match result {
+            TestedResult::Single { name, .. } => name.into_owned(),
+            TestedResult::Setup { name, .. } => format!("setup {}", name),
+            TestedResult::Teardown { name, .. } => format!("teardown {}", name),
+            TestedResult::GroupStart { name, .. } => format!("start {}", name),
+            TestedResult::GroupEnd { name, .. } => format!("end {}", name),
+        })
+        .collect()
+}
+
+#[test]
+fn tests_are_selected_by_path() {
+    assert_eq!(
+        events("password"),
+        vec![
+            "start root",
+            "start login",
+            "setup connect",
+            "valid password",
+            "wrong password",
+            "teardown disconnect",
+            "end login",
+            "end root",
+        ]
+    );
+
+    assert_eq!(
+        events("=root::logout"),
+        vec!["start root", "logout", "end root"]
+    );
+    assert_eq!(events("=logout"), Vec::<String>::new());
+
+    assert_eq!(
+        events("root::*::by n?me,logout"),
+        vec![
+            "start root",
+            "start search",
+            "setup index",
+            "by name",
+            "end search",
+            "logout",
+            "end root",
+        ]
+    );
+}
+
+#[test]
+fn dependencies_of_selected_tests_are_run() {
+    assert_eq!(
+        events("by date"),
+        vec![
+            "start root",
+            "start login",
+            "setup connect",
+            "valid password",
+            "teardown disconnect",
+            "end login",
+            "start search",
+            "setup index",
+            "by date",
+            "end search",
+            "end root",
+        ]
+    );
+}
+
+#[test]
+fn filtered_out_tests_keep_the_sequence_waiting_on_failures() {
+    let tests = group!(
+        "root",
+        Ordering::Sequence {
+            on_condition: scaffolding::OrderingCondition::Success
+        },
+        [
+            single!("t0", || false),
+            single!("t1", || true),
+            single!("t2", || true),
+            single!("t3", || true),
+        ]
+    );
+
+    let results: Vec<(String, bool, bool)> = Executor::new(tests)
+        .filter(&Filter::new(["=root::t0", "=root::t3"]))
+        .filter_map(|result| match result {
+            TestedResult::Single { name, result, .. } => {
+                Some((name.into_owned(), result.is_failure(), result.is_skipped()))
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        results,
+        vec![
+            ("t0".to_owned(), true, false),
+            ("t3".to_owned(), false, true)
+        ]
+    );
+}
@@ -0,0 +1,126 @@
+use scaffolding::{group, setup, single, teardown, Executor, TestContext, TestedResult};
+use std::sync::{Arc, Mutex};
+
+fn event(result: &TestedResult) -> String {
+    match result {
+        TestedResult::Single { name, result, .. } => format!("{} {}", name, result.short),
+        TestedResult::Setup { name, result } => format!("setup {} {}", name, result.short),
+        TestedResult::Teardown { name, result } => format!("teardown {} {}", name, result.short),
+        TestedResult::GroupStart { name, .. } => format!("start {}", name),
+        TestedResult::GroupEnd { name, .. } => format!("end {}", name),
+    }
+}
+
+#[test]
+fn teardown_runs_after_failures() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let (init, test, clean) = (Arc::clone(&log), Arc::clone(&log), Arc::clone(&log));
+    let tests = group!(
+        "group",
+        [
+            single!("panics", move || -> bool {
+                test.lock().unwrap().push("test");
+                panic!("test failure")
+            }),
+            teardown!("clean", move || clean.lock().unwrap().push("teardown")),
+            setup!("init", move || init.lock().unwrap().push("setup")),
+        ]
+    );
+
+    let events: Vec<String> = Executor::new(tests).map(|result| event(&result)).collect();
+
+    assert_eq!(
+        events,
+        vec![
+            "start group",
+            "setup init Success",
+            "panics Failure",
+            "teardown clean Success",
+            "end group",
+        ]
+    );
+    assert_eq!(*log.lock().unwrap(), vec!["setup", "test", "teardown"]);
+}
+
+#[test]
+fn failed_setup_skips_the_group() {
+    let tests = group!(
+        "group",
+        [
+            setup!("init", || false),
+            single!("test", || true),
+            group!("nested", [single!("nested test", || true)]),
+            teardown!("clean", || true),
+        ]
+    );
+
+    let results: Vec<TestedResult> = Executor::new(tests).collect();
+
+    for result in &results {
+        if let TestedResult::Single { result, .. } = result {
+            assert!(result.is_skipped());
+            assert_eq!(result.details, "setup group::init failed");
+        }
+    }
+    assert!(matches!(
+        results.iter().rev().nth(1),
+        Some(TestedResult::Teardown { result, .. }) if result.is_success()
+    ));
+}
+
+struct Database {
+    dropped: Arc<Mutex<bool>>,
+}
+
+impl Drop for Database {
+    fn drop(&mut self) {
+        *self.dropped.lock().unwrap() = true;
+    }
+}
+
+#[test]
+fn fixtures_from_setup() {
+    let dropped = Arc::new(Mutex::new(false));
+    let database = Arc::clone(&dropped);
+    let tests = group!(
+        "group",
+        [
+            setup!("database", move |context: &TestContext| {
+                context.provide(Database {
+                    dropped: Arc::clone(&database),
+                });
+                context.provide(1u32);
+            }),
+            single!("uses the database", |context: &TestContext| {
+                context.fixture::<Database>().is_some()
+            }),
+            group!(
+                "nested",
+                [
+                    setup!("override", |context: &TestContext| context.provide(2u32)),
+                    single!("sees both", |context: &TestContext| {
+                        context.fixture::<Database>().is_some()
+                            && context.fixture::<u32>().map(|value| *value) == Some(2)
+                    }),
+                ]
+            ),
+            single!("sees its own", |context: &TestContext| {
+                context.fixture::<u32>().map(|value| *value) == Some(1)
+            }),
+        ]
+    );
+
+    let mut executor = Executor::new(tests);
+    for result in &mut executor {
+        match result {
+            TestedResult::Single { name, result, .. } => assert!(result.is_success(), "{}", name),
+            TestedResult::GroupEnd { name, .. } if name == "group" => {
+                assert!(
+                    *dropped.lock().unwrap(),
+                    "fixtures are dropped with the group"
+                )
+            }
+            _ => (),
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use scaffolding::{
+    group, setting, settings::Timeout, setup, single, Html, Ordering, OrderingCondition, TestTree,
+};
+use std::{fs, time::Duration};
+
+fn tests() -> TestTree {
+    group!(
+        "root",
+        Ordering::Sequence {
+            on_condition: OrderingCondition::Finish
+        },
+        [
+            setup!("prepare", || true),
+            single!("passes", || true),
+            group!(
+                "nested",
+                [
+                    setting!(Timeout(Duration::from_millis(10))),
+                    single!("fails", || Result::<(), _>::Err("expected <b> & got 'c'")),
+                    single!("slow", || std::thread::sleep(Duration::from_millis(100))),
+                    single!("later", || true).ignore("no server"),
+                ]
+            ),
+            group!("quick", [single!("also passes", || true).tag("fast")]),
+        ]
+    )
+}
+
+#[test]
+fn report_is_a_self_contained_page() {
+    let html = tests().run().to_html();
+
+    assert!(html.starts_with("<!DOCTYPE html>"), "{}", html);
+    assert!(html.trim_end().ends_with("</html>"), "{}", html);
+    assert!(html.contains("<style>"), "{}", html);
+    assert!(html.contains("<script>"), "{}", html);
+    assert!(!html.contains("src=\"http"), "{}", html);
+    assert!(!html.contains("href=\"http"), "{}", html);
+    assert!(
+        html.contains(r#"<p class="summary">3 passed, 1 failed, 1 timeout, 1 skipped"#),
+        "{}",
+        html
+    );
+}
+
+#[test]
+fn groups_are_collapsible_and_open_on_failure() {
+    let html = tests().run().to_html();
+
+    assert!(
+        html.contains(r#"<details class="failed" open>"#)
+            && html.contains(r#"<summary><span class="name">root</span>"#),
+        "{}",
+        html
+    );
+    assert!(
+        html.contains(
+            "<details class=\"passed\">\n<summary><span class=\"name\">quick</span> <span class=\"counts\">1 passed</span>"
+        ),
+        "{}",
+        html
+    );
+}
+
+#[test]
+fn results_have_their_status_and_details() {
+    let html = tests().run().to_html();
+
+    assert!(
+        html.contains(r#"<li class="passed"><span class="status">Success</span> <span class="kind">setup </span><span class="name">prepare</span>"#),
+        "{}",
+        html
+    );
+    assert!(
+        html.contains(r#"<span class="name">also passes [fast]</span>"#),
+        "{}",
+        html
+    );
+    assert!(
+        html.contains(r#"<li class="failed"><span class="status">Failure</span>"#),
+        "{}",
+        html
+    );
+    assert!(
+        html.contains(r#"<pre class="details">expected &lt;b&gt; &amp; got &#39;c&#39;</pre>"#),
+        "{}",
+        html
+    );
+    assert!(
+        html.contains(r#"<li class="timeout"><span class="status">Timeout</span>"#),
+        "{}",
+        html
+    );
+    assert!(
+        html.contains(r#"<li class="skipped"><span class="status">Ignored</span>"#),
+        "{}",
+        html
+    );
+    for status in ["passed", "flaky", "failed", "timeout", "skipped"] {
+        assert!(
+            html.contains(&format!(r#"data-status="{}""#, status)),
+            "{}",
+            html
+        );
+    }
+}
+
+#[test]
+fn reporter_writes_every_run_in_the_file() {
+    let path = std::env::temp_dir()
+        .join(format!("scaffolding-html-{}", std::process::id()))
+        .join("report.html");
+    let mut reporter = Html::new(&path);
+
+    tests().run_with(&mut reporter);
+    single!("alone", || true).run_with(&mut reporter);
+
+    let html = fs::read_to_string(&path).unwrap();
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    assert!(
+        html.contains(r#"<span class="name">alone</span>"#),
+        "{}",
+        html
+    );
+    assert!(html.contains("4 passed"), "{}", html);
+}
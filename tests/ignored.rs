@@ -0,0 +1,91 @@
+use scaffolding::{
+    group, setup, single, todo_test, Executor, Ordering, OrderingCondition, Report, RunIgnored,
+    TestTree, TestedResult,
+};
+
+fn tests() -> TestTree {
+    group!(
+        "root",
+        Ordering::Sequence {
+            on_condition: OrderingCondition::Success
+        },
+        [
+            single!("runs", || true),
+            single!("needs a server", || true).ignore("no server in the CI"),
+            todo_test!("parses dates"),
+            group!(
+                "legacy",
+                [setup!("prepare", || true), single!("old api", || false)]
+            )
+            .ignore("being rewritten"),
+            single!("after", || true),
+        ]
+    )
+}
+
+fn events(mode: RunIgnored) -> Vec<String> {
+    Executor::new(tests())
+        .run_ignored(mode)
+        .filter_map(|result| match result {
+            TestedResult::Single { name, result, .. } | TestedResult::Setup { name, result } => {
+                Some(format!("{}: {} {}", name, result.short, result.details))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn ignored_tests_are_listed_with_their_reason() {
+    assert_eq!(
+        events(RunIgnored::No),
+        vec![
+            "runs: Success ",
+            "needs a server: Ignored no server in the CI",
+            "parses dates: Todo ",
+            "prepare: Ignored being rewritten",
+            "old api: Ignored being rewritten",
+            "after: Success ",
+        ]
+    );
+
+    let report: Report = Executor::new(tests()).run_ignored(RunIgnored::No).collect();
+    assert!(report.is_success());
+    assert_eq!(report.ignored(), 3);
+    let mut markdown = Vec::new();
+    report.report(&mut markdown).unwrap();
+    let markdown = String::from_utf8(markdown).unwrap();
+    assert!(markdown.contains("no server in the CI"), "{}", markdown);
+    assert!(markdown.contains("\n3 ignored\n"), "{}", markdown);
+}
+
+#[test]
+fn ignored_tests_can_run() {
+    assert_eq!(
+        events(RunIgnored::Include),
+        vec![
+            "runs: Success ",
+            "needs a server: Success ",
+            "parses dates: Todo ",
+            "prepare: Success ",
+            "old api: Failure ",
+            "after: Skipped ",
+        ]
+    );
+
+    assert_eq!(
+        events(RunIgnored::Only),
+        vec![
+            "needs a server: Success ",
+            "prepare: Success ",
+            "old api: Failure ",
+        ]
+    );
+}
+
+#[test]
+#[should_panic(expected = r#"invalid SCAFFOLDING_IGNORED: "onyl", expected one of: only, include"#)]
+fn unknown_modes_are_rejected() {
+    std::env::set_var("SCAFFOLDING_IGNORED", "onyl");
+    RunIgnored::from_env();
+}
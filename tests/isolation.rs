@@ -0,0 +1,192 @@
+mod common;
+
+use common::results;
+use scaffolding::{
+    group, setting,
+    settings::{Capture, GracePeriod, Isolation, Timeout},
+    setup, single, teardown, Executor, FailureReason, Ordering, OrderingCondition, Outcome,
+    TestContext,
+};
+use std::{path::PathBuf, thread::sleep, time::Duration};
+
+fn overflow(depth: u64) -> u64 {
+    if depth == u64::MAX {
+        return 0;
+    }
+    let buffer = [depth; 1_024];
+    std::hint::black_box(&buffer);
+    overflow(depth + 1) + buffer[0]
+}
+
+#[test]
+fn crashes_are_reported() {
+    let tests = group!(
+        "root",
+        [
+            setting!(Isolation::Process),
+            setting!(Capture::OnFailure),
+            setting!(Timeout(Duration::from_millis(200))),
+            setting!(GracePeriod(Duration::from_millis(10))),
+            single!("passes", || true),
+            single!("fails", || false),
+            single!("panics", || -> bool { panic!("expected panic") }),
+            single!("aborts", || -> bool { std::process::abort() }),
+            single!("exits", || -> bool { std::process::exit(3) }),
+            single!("overflows", || overflow(0) > 0),
+            single!("hangs", || -> bool {
+                loop {
+                    sleep(Duration::from_secs(1))
+                }
+            }),
+        ]
+    );
+
+    let results = results(Executor::from_env(tests));
+    assert!(results["passes"].is_success());
+    assert!(matches!(
+        results["fails"].outcome,
+        Outcome::Failure {
+            reason: FailureReason::Failed
+        }
+    ));
+    assert!(matches!(
+        results["panics"].outcome,
+        Outcome::Failure {
+            reason: FailureReason::Panicked
+        }
+    ));
+    assert!(matches!(
+        results["aborts"].outcome,
+        Outcome::Failure {
+            reason: FailureReason::Aborted
+        }
+    ));
+    assert!(matches!(
+        results["exits"].outcome,
+        Outcome::Failure {
+            reason: FailureReason::Exited { code: 3 }
+        }
+    ));
+    assert!(matches!(
+        results["overflows"].outcome,
+        Outcome::Failure {
+            reason: FailureReason::StackOverflow
+        }
+    ));
+    assert!(results["hangs"].is_timeout());
+}
+
+#[test]
+fn setups_provide_fixtures() {
+    struct Answer(u32);
+
+    let tests = group!(
+        "root",
+        [
+            setting!(Isolation::Process),
+            setup!("answer", |context: &TestContext| context
+                .provide(Answer(42))),
+            single!("reads the answer", |context: &TestContext| {
+                context.fixture::<Answer>().map(|answer| answer.0) == Some(42)
+                    && context.path() == "root::reads the answer"
+            }),
+        ]
+    );
+
+    assert!(results(Executor::from_env(tests))["reads the answer"].is_success());
+}
+
+#[test]
+fn teardowns_release_what_the_setups_acquired() {
+    struct Marker(PathBuf);
+
+    // the children run this function again, every process leaves its
+    // marker in the directory until its teardown deletes it
+    let directory = std::env::temp_dir().join("scaffolding-teardowns-release");
+    let _ = std::fs::remove_dir_all(&directory);
+    std::fs::create_dir_all(&directory).unwrap();
+
+    let markers = directory.clone();
+    let tests = group!(
+        "root",
+        [
+            setting!(Isolation::Process),
+            setup!("create", move |context: &TestContext| {
+                let marker = markers.join(std::process::id().to_string());
+                std::fs::write(&marker, "").unwrap();
+                context.provide(Marker(marker));
+            }),
+            single!("sees the marker", |context: &TestContext| {
+                context
+                    .fixture::<Marker>()
+                    .is_some_and(|marker| marker.0.exists())
+            }),
+            teardown!("delete", |context: &TestContext| {
+                let marker = context.fixture::<Marker>().unwrap();
+                std::fs::remove_file(&marker.0).is_ok()
+            }),
+        ]
+    );
+
+    assert!(results(Executor::from_env(tests))["sees the marker"].is_success());
+    let left: Vec<_> = std::fs::read_dir(&directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert!(left.is_empty(), "{:?}", left);
+}
+
+#[test]
+fn children_do_not_write_the_reports() {
+    // the children run this function again, they build the same path
+    let path = std::env::temp_dir().join("scaffolding-children-do-not-write-the-reports.tap");
+    std::env::set_var("SCAFFOLDING_REPORTER", format!("tap:{}", path.display()));
+
+    let tests = group!(
+        "root",
+        Ordering::Sequence {
+            on_condition: OrderingCondition::Finish
+        },
+        [
+            single!("before", || true),
+            group!(
+                "isolated",
+                [
+                    setting!(Isolation::Process),
+                    single!("first", || true),
+                    single!("second", || true),
+                ]
+            ),
+            single!("after", || true),
+        ]
+    );
+    tests.run();
+
+    let tap = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert!(!tap.contains('\0'), "{:?}", tap);
+    assert!(tap.contains("ok 1 - before"), "{}", tap);
+    assert!(tap.contains("ok 3 - after"), "{}", tap);
+}
+
+#[test]
+fn output_of_isolated_tests_is_captured() {
+    let tests = group!(
+        "root",
+        [
+            setting!(Isolation::Process),
+            setting!(Capture::OnFailure),
+            single!("prints", || {
+                println!("from the process");
+                eprintln!("and its error");
+                false
+            }),
+        ]
+    );
+
+    let results = results(Executor::from_env(tests));
+    assert_eq!(
+        results["prints"].output,
+        "from the process\nand its error\n"
+    );
+}
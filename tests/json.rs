@@ -0,0 +1,88 @@
+use scaffolding::{group, setup, single, Json, Ordering, OrderingCondition, TestTree};
+
+fn tests() -> TestTree {
+    group!(
+        "root",
+        Ordering::Sequence {
+            on_condition: OrderingCondition::Finish
+        },
+        [
+            setup!("prepare", || true),
+            single!("passes", || true),
+            group!(
+                "nested",
+                [
+                    single!("fails", || Result::<(), _>::Err("\"quoted\"\n\tand tabbed")),
+                    single!("later", || true).ignore("no server"),
+                ]
+            ),
+        ]
+    )
+}
+
+const EXEC_TIME: &str = r#""exec_time": "#;
+
+fn json(tests: TestTree) -> Vec<String> {
+    let mut output = Vec::new();
+    tests.run_with(&mut Json::new_with(&mut output));
+    String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| {
+            // the durations vary from a run to the other
+            let mut line = line.to_owned();
+            if let Some(start) = line.find(EXEC_TIME) {
+                let value = start + EXEC_TIME.len();
+                let end = line[value..]
+                    .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | 'e' | '-')))
+                    .map_or(line.len(), |end| value + end);
+                line.replace_range(start..end, "EXEC_TIME");
+            }
+            line
+        })
+        .collect()
+}
+
+#[test]
+fn events_follow_the_format_of_the_test_harness() {
+    assert_eq!(
+        json(tests()),
+        vec![
+            r#"{ "type": "suite", "event": "started", "test_count": 3 }"#,
+            r#"{ "type": "test", "event": "started", "name": "root::setup prepare" }"#,
+            r#"{ "type": "test", "name": "root::setup prepare", "event": "ok", EXEC_TIME, "scaffolding": { "group": "root", "kind": "setup", "short": "Success" } }"#,
+            r#"{ "type": "test", "event": "started", "name": "root::passes" }"#,
+            r#"{ "type": "test", "name": "root::passes", "event": "ok", EXEC_TIME, "scaffolding": { "group": "root", "kind": "test", "short": "Success" } }"#,
+            r#"{ "type": "test", "event": "started", "name": "root::nested::fails" }"#,
+            r#"{ "type": "test", "name": "root::nested::fails", "event": "failed", EXEC_TIME, "stdout": "\"quoted\"\n\tand tabbed", "scaffolding": { "group": "root::nested", "kind": "test", "short": "Failure", "reason": "failed" } }"#,
+            r#"{ "type": "test", "event": "started", "name": "root::nested::later" }"#,
+            r#"{ "type": "test", "name": "root::nested::later", "event": "ignored", EXEC_TIME, "message": "no server", "scaffolding": { "group": "root::nested", "kind": "test", "short": "Ignored" } }"#,
+            r#"{ "type": "suite", "event": "failed", "passed": 2, "failed": 1, "ignored": 1, "measured": 0, "filtered_out": 0, EXEC_TIME }"#,
+        ]
+    );
+}
+
+#[test]
+fn filtered_out_tests_are_counted() {
+    // the filter is read from the environment, it keeps all the tests of
+    // `events_follow_the_format_of_the_test_harness` running concurrently
+    std::env::set_var("SCAFFOLDING_FILTER", "root::,filtered::kept");
+    let tests = group!(
+        "filtered",
+        [
+            single!("kept", || true),
+            single!("dropped", || true),
+            single!("dropped too", || true),
+        ]
+    );
+
+    let events = json(tests);
+    assert_eq!(
+        events.first().unwrap(),
+        r#"{ "type": "suite", "event": "started", "test_count": 1 }"#
+    );
+    assert_eq!(
+        events.last().unwrap(),
+        r#"{ "type": "suite", "event": "ok", "passed": 1, "failed": 0, "ignored": 0, "measured": 0, "filtered_out": 2, EXEC_TIME }"#
+    );
+}
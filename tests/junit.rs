@@ -0,0 +1,118 @@
+use scaffolding::{
+    group, setting, settings::Timeout, setup, single, JUnit, Ordering, OrderingCondition, Report,
+    TestTree,
+};
+use std::{fs, time::Duration};
+
+fn tests() -> TestTree {
+    group!(
+        "root",
+        Ordering::Sequence {
+            on_condition: OrderingCondition::Finish
+        },
+        [
+            setup!("prepare", || true),
+            single!("passes", || true),
+            single!("fails", || Result::<(), _>::Err("expected <1> & got <2>")),
+            single!("panics", || -> () { panic!("boom") }),
+            group!(
+                "nested",
+                [
+                    setting!(Timeout(Duration::from_millis(10))),
+                    single!("slow", || std::thread::sleep(Duration::from_millis(100))),
+                    single!("later", || true).ignore("not yet"),
+                ]
+            ),
+        ]
+    )
+}
+
+fn junit(report: &Report) -> String {
+    let mut output = Vec::new();
+    report.write_junit(&mut output).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn groups_are_suites_with_dotted_names() {
+    let xml = junit(&tests().run());
+
+    assert!(xml.starts_with("<?xml"), "{}", xml);
+    assert!(
+        xml.contains(r#"<testsuites tests="6" failures="3" errors="0" skipped="1""#),
+        "{}",
+        xml
+    );
+    assert!(
+        xml.contains(r#"<testsuite name="root" tests="4" failures="2" errors="0" skipped="0""#),
+        "{}",
+        xml
+    );
+    assert!(
+        xml.contains(
+            r#"<testsuite name="root.nested" tests="2" failures="1" errors="0" skipped="1""#
+        ),
+        "{}",
+        xml
+    );
+    assert!(
+        xml.contains(r#"<testcase name="setup prepare" classname="root""#),
+        "{}",
+        xml
+    );
+    assert!(
+        xml.contains(r#"<testcase name="passes" classname="root""#),
+        "{}",
+        xml
+    );
+    assert!(xml.ends_with("</testsuites>\n"), "{}", xml);
+}
+
+#[test]
+fn failures_keep_their_reason_and_details() {
+    let xml = junit(&tests().run());
+
+    assert!(
+        xml.contains(
+            r#"<failure message="Failure" type="failed">expected &lt;1&gt; &amp; got &lt;2&gt;</failure>"#
+        ),
+        "{}",
+        xml
+    );
+    assert!(
+        xml.contains(r#"<failure message="Failure" type="panicked">"#),
+        "{}",
+        xml
+    );
+    assert!(xml.contains("boom"), "{}", xml);
+    assert!(
+        xml.contains(r#"<failure message="Timeout" type="timedout""#),
+        "{}",
+        xml
+    );
+    assert!(
+        xml.contains(r#"<skipped message="Ignored">not yet</skipped>"#),
+        "{}",
+        xml
+    );
+}
+
+#[test]
+fn reporter_writes_every_run_in_the_file() {
+    let path = std::env::temp_dir()
+        .join(format!("scaffolding-junit-{}", std::process::id()))
+        .join("report.xml");
+    let mut reporter = JUnit::new(&path);
+
+    tests().run_with(&mut reporter);
+    single!("alone", || true).run_with(&mut reporter);
+
+    let xml = fs::read_to_string(&path).unwrap();
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    assert!(xml.contains(r#"<testsuites tests="7""#), "{}", xml);
+    assert!(
+        xml.contains(r#"<testcase name="alone" classname="tests""#),
+        "{}",
+        xml
+    );
+}
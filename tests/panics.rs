@@ -0,0 +1,41 @@
+mod common;
+
+use common::results;
+use scaffolding::{group, setting, settings::Backtrace, single, Executor, Ordering};
+
+#[inline(never)]
+fn failing_assertion(value: u32) -> bool {
+    assert_eq!(value, 42, "the answer");
+    true
+}
+
+#[test]
+fn panics_are_described() {
+    let tests = group!(
+        "root",
+        Ordering::Any,
+        [
+            setting!(Backtrace::Off),
+            single!("static", || -> bool { panic!("static message") }),
+            single!("formatted", || -> bool { panic!("formatted {}", 42) }),
+            single!("payload", || -> bool { std::panic::panic_any(42u32) }),
+            setting!(Backtrace::Short),
+            single!("backtrace", || failing_assertion(0)),
+        ]
+    );
+
+    let results = results(Executor::new(tests));
+
+    let details = &results["static"].details;
+    assert!(details.starts_with("test panicked at tests/panics.rs:"));
+    assert!(details.ends_with(":\nstatic message"));
+    assert!(results["formatted"].details.ends_with(":\nformatted 42"));
+    assert!(results["payload"].details.ends_with(":\nBox<dyn Any>"));
+
+    let details = &results["backtrace"].details;
+    assert!(details.contains("the answer"));
+    assert!(details.contains("stack backtrace:"));
+    assert!(details.contains("failing_assertion"));
+    assert!(!details.contains("rust_begin_unwind"));
+    assert!(!details.contains("catch_unwind"));
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use common::results;
+use scaffolding::{group, setting, settings::Repeat, single, Executor};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+#[test]
+fn tests_are_repeated() {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let rare = Arc::new(AtomicUsize::new(0));
+    let timed = Arc::new(AtomicUsize::new(0));
+    let tests = group!(
+        "root",
+        [
+            setting!(Repeat::Times(10)),
+            single!("passing", {
+                let runs = Arc::clone(&runs);
+                move || {
+                    runs.fetch_add(1, Ordering::SeqCst);
+                }
+            }),
+            single!("rare failure", {
+                let rare = Arc::clone(&rare);
+                move || -> Result<(), String> {
+                    match rare.fetch_add(1, Ordering::SeqCst) {
+                        3 => Err("failed on the fourth run".to_owned()),
+                        7 => Err("failed on the eighth run".to_owned()),
+                        _ => Ok(()),
+                    }
+                }
+            }),
+            setting!(Repeat::For(Duration::from_millis(50))),
+            single!("for a while", {
+                let timed = Arc::clone(&timed);
+                move || {
+                    timed.fetch_add(1, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(10));
+                }
+            }),
+        ]
+    );
+
+    let results = results(Executor::new(tests));
+
+    let result = &results["passing"];
+    assert!(result.is_success());
+    assert_eq!(runs.load(Ordering::SeqCst), 10);
+    assert!(result
+        .details
+        .starts_with("ran 10 times: 10 passed, 0 failed\nduration min "));
+
+    let result = &results["rare failure"];
+    assert!(result.is_failure());
+    assert_eq!(rare.load(Ordering::SeqCst), 10);
+    assert!(result
+        .details
+        .starts_with("ran 10 times: 8 passed, 2 failed\n"));
+    assert!(result
+        .details
+        .ends_with("first failure (run 4):\nfailed on the fourth run"));
+
+    assert!(results["for a while"].is_success());
+    let timed = timed.load(Ordering::SeqCst);
+    assert!((2..=6).contains(&timed), "ran {} times", timed);
+}
@@ -0,0 +1,147 @@
+use scaffolding::{
+    group, scaffold_with, setup, single, FanOut, Ordering, OrderingCondition, Progress, Reporter,
+    Summary, TestName, TestTree, TestedResult,
+};
+use std::{
+    io,
+    sync::{Arc, Mutex},
+};
+
+fn tests() -> TestTree {
+    group!(
+        "root",
+        Ordering::Sequence {
+            on_condition: OrderingCondition::Finish
+        },
+        [
+            setup!("prepare", || true),
+            single!("passes", || true),
+            single!("fails", || false),
+            group!("nested", [single!("ignored", || true).ignore("not yet")]),
+        ]
+    )
+}
+
+/// records the events of the run
+#[derive(Clone, Default)]
+struct Recorder {
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+impl Recorder {
+    fn events(&self) -> Vec<String> {
+        self.events.lock().unwrap().clone()
+    }
+
+    fn push(&self, event: String) -> io::Result<()> {
+        self.events.lock().unwrap().push(event);
+        Ok(())
+    }
+}
+
+impl Reporter for Recorder {
+    fn run_start(&mut self) -> io::Result<()> {
+        self.push("run start".to_owned())
+    }
+
+    fn group_start(&mut self, name: &TestName, _: &Ordering) -> io::Result<()> {
+        self.push(format!("group start {}", name))
+    }
+
+    fn group_end(&mut self, name: &TestName, _: &Ordering) -> io::Result<()> {
+        self.push(format!("group end {}", name))
+    }
+
+    fn test_start(&mut self, name: &TestName) -> io::Result<()> {
+        self.push(format!("test start {}", name))
+    }
+
+    fn test_result(&mut self, result: &TestedResult) -> io::Result<()> {
+        match result {
+            TestedResult::Single { name, result, .. }
+            | TestedResult::Setup { name, result }
+            | TestedResult::Teardown { name, result } => {
+                self.push(format!("result {} {}", name, result.short))
+            }
+            _ => panic!("only the results of the tests are reported"),
+        }
+    }
+
+    fn run_end(&mut self, summary: &Summary) -> io::Result<()> {
+        self.push(format!(
+            "run end {} passed, {} failed, {} ignored",
+            summary.passed, summary.failed, summary.ignored
+        ))
+    }
+}
+
+#[test]
+fn reporter_receives_the_events_in_order() {
+    let mut recorder = Recorder::default();
+    let report = tests().run_with(&mut recorder);
+
+    assert!(!report.is_success());
+    assert_eq!(
+        recorder.events(),
+        vec![
+            "run start",
+            "group start root",
+            "result prepare Success",
+            "test start passes",
+            "result passes Success",
+            "test start fails",
+            "result fails Failure",
+            "group start nested",
+            "test start ignored",
+            "result ignored Ignored",
+            "group end nested",
+            "group end root",
+            "run end 1 passed, 1 failed, 1 ignored",
+        ]
+    );
+}
+
+#[test]
+fn fan_out_sends_the_events_to_every_reporter() {
+    let first = Recorder::default();
+    let second = Recorder::default();
+    let mut fan_out = FanOut::new().with(first.clone()).with(second.clone());
+
+    tests().run_with(&mut fan_out);
+
+    assert_eq!(first.events().len(), 13);
+    assert_eq!(first.events(), second.events());
+}
+
+#[test]
+fn progress_is_a_reporter() {
+    let mut output = Vec::new();
+    tests().run_with(&mut Progress::new_with(&mut output));
+
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("passes"), "{}", output);
+    assert!(output.contains("1 ignored"), "{}", output);
+}
+
+#[test]
+#[should_panic(expected = "failed 1 out of 2, 1 ignored")]
+fn scaffold_with_panics_on_failure() {
+    let recorder = Recorder::default();
+    scaffold_with(tests(), &mut FanOut::new().with(recorder));
+}
+
+#[test]
+fn summary_displays_the_counts() {
+    let summary = Summary {
+        passed: 3,
+        failed: 1,
+        ignored: 2,
+        ..Summary::default()
+    };
+    assert!(!summary.is_success());
+    assert_eq!(summary.tested(), 4);
+    assert_eq!(
+        summary.to_string(),
+        "3 passed, 1 failed, 2 ignored in 0.00ns"
+    );
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use common::results;
+use scaffolding::{group, setting, settings::Retry, single, Executor, TestContext};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+#[test]
+fn failing_tests_are_retried() {
+    let flaky = Arc::new(AtomicUsize::new(0));
+    let failing = Arc::new(AtomicUsize::new(0));
+    let passing = Arc::new(AtomicUsize::new(0));
+    let tests = group!(
+        "root",
+        [
+            setting!(Retry {
+                attempts: 3,
+                backoff: Duration::from_millis(1),
+            }),
+            single!("flaky", {
+                let flaky = Arc::clone(&flaky);
+                move |context: &TestContext| {
+                    let attempt = flaky.fetch_add(1, Ordering::SeqCst);
+                    context.log(format!("attempt {}", attempt));
+                    attempt == 2
+                }
+            }),
+            single!("failing", {
+                let failing = Arc::clone(&failing);
+                move || -> bool {
+                    failing.fetch_add(1, Ordering::SeqCst);
+                    panic!("always failing")
+                }
+            }),
+            single!("passing", {
+                let passing = Arc::clone(&passing);
+                move || passing.fetch_add(1, Ordering::SeqCst) == 0
+            }),
+        ]
+    );
+
+    let results = results(Executor::new(tests));
+
+    let result = &results["flaky"];
+    assert!(result.is_flaky());
+    assert!(result.is_success());
+    assert_eq!(flaky.load(Ordering::SeqCst), 3);
+    assert!(result.details.starts_with("attempt 1: Failure after "));
+    assert!(result.details.contains("attempt 0\nattempt 2: Failure"));
+    assert!(result.details.contains("attempt 3: Success"));
+
+    let result = &results["failing"];
+    assert!(result.is_failure());
+    assert_eq!(failing.load(Ordering::SeqCst), 3);
+    assert_eq!(result.details.matches("always failing").count(), 3);
+
+    assert!(results["passing"].outcome.is_success());
+    assert!(!results["passing"].is_flaky());
+    assert_eq!(passing.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,258 @@
+use scaffolding::{
+    group, setup, single, Executor, Ordering, OrderingCondition, Report, Shard, TestTree,
+    TestedResult,
+};
+use std::{collections::HashSet, time::Duration};
+
+fn tests() -> TestTree {
+    group!(
+        "root",
+        [
+            group!(
+                "first",
+                [
+                    setup!("prepare", || true),
+                    single!("a", || true),
+                    single!("b", || true),
+                    single!("c", || true),
+                    single!("d", || true),
+                ]
+            ),
+            group!(
+                "second",
+                [
+                    single!("e", || true),
+                    single!("f", || true),
+                    single!("g", || true),
+                    single!("h", || true),
+                ]
+            ),
+        ]
+    )
+}
+
+fn tests_of(results: &[TestedResult]) -> Vec<String> {
+    results
+        .iter()
+        .filter_map(|result| match result {
+            TestedResult::Single { name, .. } => Some(name.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn shards_are_disjoint_and_merge_back() {
+    let mut all = Vec::new();
+    let mut reports = Vec::new();
+    for index in 0..3 {
+        let results: Vec<TestedResult> = Executor::new(tests())
+            .shard(&Shard::new(index, 3))
+            .collect();
+        let again: Vec<TestedResult> = Executor::new(tests())
+            .shard(&Shard::new(index, 3))
+            .collect();
+        assert_eq!(tests_of(&results), tests_of(&again), "shards are stable");
+
+        all.extend(tests_of(&results));
+        reports.push(results.into_iter().collect::<Report>());
+    }
+
+    all.sort();
+    assert_eq!(all, vec!["a", "b", "c", "d", "e", "f", "g", "h"]);
+
+    let merged = Report::merge(&tests(), reports);
+    assert!(merged.is_success());
+    let paths: HashSet<String> = merged.durations().into_keys().collect();
+    assert_eq!(paths.len(), 8);
+    assert!(paths.contains("root::first::a"), "{:?}", paths);
+    assert!(paths.contains("root::second::h"), "{:?}", paths);
+    if let Report::Group { reports, .. } = &merged {
+        assert_eq!(reports.len(), 2, "{:?}", merged);
+    } else {
+        panic!("the root group should be merged: {:?}", merged)
+    }
+}
+
+/// the names of the tests of the report, in order
+fn names(report: &Report) -> Vec<String> {
+    match report {
+        Report::Grouped { reports } | Report::Group { reports, .. } => {
+            reports.iter().flat_map(names).collect()
+        }
+        Report::Test { name, .. } => vec![name.to_string()],
+        Report::Setup { .. } | Report::Teardown { .. } => Vec::new(),
+    }
+}
+
+#[test]
+fn dependencies_run_on_one_shard() {
+    let tests = || {
+        group!(
+            "root",
+            [
+                single!("a", || true),
+                single!("b", || true).depends_on("a"),
+                single!("c", || true).depends_on("a"),
+                single!("d", || true).depends_on("a"),
+                single!("e", || true).depends_on("a"),
+                single!("f", || true),
+                single!("g", || true),
+                single!("h", || true),
+            ]
+        )
+    };
+
+    let mut all = Vec::new();
+    let mut reports = Vec::new();
+    for index in 0..3 {
+        let results: Vec<TestedResult> = Executor::new(tests())
+            .shard(&Shard::new(index, 3))
+            .collect();
+        let executed = tests_of(&results);
+        if executed.contains(&"a".to_owned()) {
+            for dependent in ["b", "c", "d", "e"] {
+                assert!(executed.contains(&dependent.to_owned()), "{:?}", executed);
+            }
+        }
+        all.extend(executed);
+        reports.push(results.into_iter().collect::<Report>());
+    }
+
+    all.sort();
+    assert_eq!(all, vec!["a", "b", "c", "d", "e", "f", "g", "h"]);
+
+    let merged = Report::merge(&tests(), reports);
+    assert!(merged.is_success());
+    assert_eq!(
+        names(&merged),
+        vec!["a", "b", "c", "d", "e", "f", "g", "h"],
+        "{:?}",
+        merged
+    );
+}
+
+#[test]
+fn sequences_run_on_one_shard() {
+    let tests = || {
+        group!(
+            "root",
+            [
+                group!(
+                    "sequence",
+                    Ordering::Sequence {
+                        on_condition: OrderingCondition::Success
+                    },
+                    [
+                        single!("t1", || false),
+                        single!("t2", || true),
+                        single!("t3", || true),
+                        single!("t4", || true),
+                    ]
+                ),
+                single!("x", || true),
+                single!("y", || true),
+            ]
+        )
+    };
+
+    let mut all = Vec::new();
+    for index in 0..3 {
+        let results: Vec<TestedResult> = Executor::new(tests())
+            .shard(&Shard::new(index, 3))
+            .collect();
+        for result in &results {
+            if let TestedResult::Single { name, result, .. } = result {
+                match name.as_ref() {
+                    "t1" => assert!(result.is_failure()),
+                    "t2" | "t3" | "t4" => assert!(result.is_skipped(), "{} {:?}", name, result),
+                    _ => assert!(result.is_success()),
+                }
+            }
+        }
+        let executed = tests_of(&results);
+        let sequence = executed.iter().filter(|name| name.starts_with('t')).count();
+        assert!(sequence == 0 || sequence == 4, "{:?}", executed);
+        all.extend(executed);
+    }
+
+    all.sort();
+    assert_eq!(all, vec!["t1", "t2", "t3", "t4", "x", "y"]);
+}
+
+#[test]
+fn merge_keeps_every_test_once_in_the_order_of_the_tree() {
+    let tree = group!(
+        "root",
+        [
+            single!("a", || true),
+            single!("b", || true),
+            single!("c", || true),
+            single!("d", || true),
+            single!("e", || true),
+        ]
+    );
+    let first = group!(
+        "root",
+        [
+            single!("a", || true),
+            single!("c", || true),
+            single!("e", || true),
+        ]
+    );
+    let second = group!(
+        "root",
+        [
+            single!("a", || false),
+            single!("b", || true),
+            single!("d", || true),
+        ]
+    );
+
+    let merged = Report::merge(
+        &tree,
+        vec![
+            Executor::new(first).collect::<Report>(),
+            Executor::new(second).collect(),
+        ],
+    );
+    assert_eq!(
+        names(&merged),
+        vec!["a", "b", "c", "d", "e"],
+        "{:?}",
+        merged
+    );
+    assert!(!merged.is_success(), "the failure of a test is kept");
+}
+
+#[test]
+fn shards_are_balanced_on_durations() {
+    let durations = Shard::parse_durations(
+        "2000 root::first::a\n\
+         800 root::second::e\n\
+         100 root::first::b\n\
+         100 root::first::c\n\
+         100 root::first::d\n\
+         100 root::second::f\n\
+         100 root::second::g\n\
+         100 root::second::h\n",
+    );
+    assert_eq!(durations["root::first::a"], Duration::from_millis(2000));
+
+    let shards: Vec<Vec<String>> = (0..2)
+        .map(|index| {
+            let results: Vec<TestedResult> = Executor::new(tests())
+                .shard(&Shard::balanced(index, 2, durations.clone()))
+                .collect();
+            tests_of(&results)
+        })
+        .collect();
+    assert_eq!(shards[0], vec!["a"]);
+    assert_eq!(shards[1].len(), 7);
+
+    let report: Report = Executor::new(tests()).collect();
+    let mut recorded = Vec::new();
+    report.write_durations(&mut recorded).unwrap();
+    let recorded = Shard::parse_durations(&String::from_utf8(recorded).unwrap());
+    assert_eq!(recorded.len(), 8);
+}
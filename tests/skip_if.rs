@@ -0,0 +1,68 @@
+use scaffolding::{group, setting, settings::SkipIf, single, Executor, TestedResult};
+
+#[test]
+fn tests_are_skipped_at_run_time() {
+    let tests = group!(
+        "root",
+        [
+            single!("before", || true),
+            group!(
+                "needs tools",
+                [
+                    setting!(SkipIf::not_on_path("surely-not-a-real-binary")
+                        .or(SkipIf::env_not_set("SCAFFOLDING_SURELY_NOT_SET"))),
+                    single!("uses the tool", || -> bool { panic!("should be skipped") }),
+                ]
+            ),
+            group!(
+                "conditions",
+                [
+                    setting!(SkipIf::env_not_set("PATH")),
+                    single!("path is set", || true),
+                    setting!(SkipIf::new(|| Some("custom reason".to_owned()))),
+                    single!("custom", || false),
+                    setting!(SkipIf::new(|| -> Option<String> { panic!("oops") })),
+                    single!("broken condition", || true),
+                ]
+            ),
+        ]
+    );
+
+    let results: Vec<(String, String, String)> = Executor::new(tests)
+        .filter_map(|result| match result {
+            TestedResult::Single { name, result, .. } => Some((
+                name.into_owned(),
+                result.short.into_owned(),
+                result.details.lines().next().unwrap_or_default().to_owned(),
+            )),
+            _ => None,
+        })
+        .collect();
+
+    let expected = [
+        ("before", "Success", ""),
+        (
+            "uses the tool",
+            "Skipped",
+            "binary `surely-not-a-real-binary` not on PATH",
+        ),
+        ("path is set", "Success", ""),
+        ("custom", "Skipped", "custom reason"),
+        (
+            "broken condition",
+            "Failure",
+            "the condition to skip the test failed:",
+        ),
+    ];
+    let expected: Vec<(String, String, String)> = expected
+        .iter()
+        .map(|(name, short, details)| {
+            (
+                (*name).to_owned(),
+                (*short).to_owned(),
+                (*details).to_owned(),
+            )
+        })
+        .collect();
+    assert_eq!(results, expected);
+}
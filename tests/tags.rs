@@ -0,0 +1,151 @@
+use scaffolding::{
+    group, single, Executor, Ordering, Progress, Report, TagExpression, TagSummary, TestTree,
+    TestedResult,
+};
+
+fn tests() -> TestTree {
+    group!(
+        "root",
+        [
+            group!(
+                "database",
+                [
+                    single!("connects", || true),
+                    single!("migrates", || false).tag("slow"),
+                ]
+            )
+            .tag("db"),
+            single!("downloads", tags = ["slow", "network-local"], || true),
+            single!("adds", || true),
+        ]
+    )
+}
+
+fn tags_of_selected(expression: &str) -> Vec<(String, Vec<String>)> {
+    Executor::new(tests())
+        .filter_tags(&expression.parse().unwrap())
+        .filter_map(|result| match result {
+            TestedResult::Single { name, tags, .. } => Some((
+                name.into_owned(),
+                tags.into_iter().map(|tag| tag.into_owned()).collect(),
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn tags_are_inherited_and_select_the_tests() {
+    assert_eq!(
+        tags_of_selected("db"),
+        vec![
+            ("connects".to_owned(), vec!["db".to_owned()]),
+            (
+                "migrates".to_owned(),
+                vec!["db".to_owned(), "slow".to_owned()]
+            ),
+        ]
+    );
+    assert_eq!(
+        tags_of_selected("slow & !db"),
+        vec![(
+            "downloads".to_owned(),
+            vec!["slow".to_owned(), "network-local".to_owned()]
+        )]
+    );
+    assert_eq!(
+        tags_of_selected("!(db | network-local)"),
+        vec![("adds".to_owned(), vec![])]
+    );
+}
+
+#[test]
+fn invalid_expressions() {
+    for expression in &["", "slow &", "(db", "db)", "!"] {
+        assert!(
+            expression.parse::<TagExpression>().is_err(),
+            "{:?} should not parse",
+            expression
+        );
+    }
+}
+
+#[test]
+fn tags_in_the_macros() {
+    let tests = group!(
+        "root",
+        Ordering::Shuffled { seed: Some(1) },
+        tags = ["team"],
+        [
+            group!(
+                "inner",
+                tags = ["db"],
+                [single!("a", tags = ["slow",], || true)]
+            ),
+            single!("b", || true),
+        ]
+    );
+
+    let mut tags: Vec<(String, Vec<String>)> = Executor::new(tests)
+        .filter_map(|result| match result {
+            TestedResult::Single { name, tags, .. } => Some((
+                name.into_owned(),
+                tags.into_iter().map(|tag| tag.into_owned()).collect(),
+            )),
+            _ => None,
+        })
+        .collect();
+    tags.sort();
+    assert_eq!(
+        tags,
+        vec![
+            (
+                "a".to_owned(),
+                vec!["team".to_owned(), "db".to_owned(), "slow".to_owned()]
+            ),
+            ("b".to_owned(), vec!["team".to_owned()]),
+        ]
+    );
+}
+
+#[test]
+fn progress_prints_the_tags() {
+    let mut output = Vec::new();
+    tests().run_with(&mut Progress::new_with(&mut output));
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains("migrates [db, slow]"), "{}", output);
+    assert!(output.contains("adds\n"), "{}", output);
+}
+
+#[test]
+fn report_by_tag() {
+    let report: Report = Executor::new(tests()).collect();
+    let by_tag = report.by_tag();
+    assert_eq!(
+        by_tag.get("slow"),
+        Some(&TagSummary {
+            passed: 1,
+            failed: 1,
+            skipped: 0
+        })
+    );
+    assert_eq!(
+        by_tag.get("db"),
+        Some(&TagSummary {
+            passed: 1,
+            failed: 1,
+            skipped: 0
+        })
+    );
+
+    let mut markdown = Vec::new();
+    report.report(&mut markdown).unwrap();
+    let markdown = String::from_utf8(markdown).unwrap();
+    assert!(
+        markdown.contains("1. `migrates` [db, slow] ("),
+        "{}",
+        markdown
+    );
+    assert!(markdown.contains("| `slow` | 1 | 1 | 0 |"), "{}", markdown);
+}
@@ -0,0 +1,113 @@
+use scaffolding::{group, setup, single, todo_test, Ordering, OrderingCondition, Tap, TestTree};
+use std::io;
+
+fn tests() -> TestTree {
+    group!(
+        "root",
+        Ordering::Sequence {
+            on_condition: OrderingCondition::Finish
+        },
+        [
+            setup!("prepare", || true),
+            single!("passes", || true),
+            single!("fails #1", || Result::<(), _>::Err("not equal:\n1 != 2")),
+            group!(
+                "nested",
+                [
+                    single!("later", || true).ignore("no server"),
+                    todo_test!("parses dates"),
+                ]
+            ),
+        ]
+    )
+}
+
+fn tap(tests: TestTree) -> String {
+    let mut output = Vec::new();
+    tests.run_with(&mut Tap::new_with(&mut output));
+    String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("duration_ms:"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn groups_are_subtests() {
+    assert_eq!(
+        tap(tests()),
+        [
+            "TAP version 14",
+            "# Subtest: root",
+            "    ok 1 - setup prepare",
+            "    ok 2 - passes",
+            "    not ok 3 - fails \\#1",
+            "      ---",
+            "      message: \"Failure\"",
+            "      severity: fail",
+            "      reason: failed",
+            "      details: |-",
+            "        not equal:",
+            "        1 != 2",
+            "      ...",
+            "    # Subtest: nested",
+            "        ok 1 - later # SKIP no server",
+            "        not ok 2 - parses dates # TODO",
+            "        1..2",
+            "    ok 4 - nested",
+            "    1..4",
+            "not ok 1 - root",
+            "1..1",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn every_run_is_a_document() {
+    let mut output = Vec::new();
+    {
+        let mut reporter = Tap::new_with(&mut output);
+        single!("first", || true).run_with(&mut reporter);
+        single!("second", || true).run_with(&mut reporter);
+    }
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "TAP version 14\nok 1 - first\n1..1\nTAP version 14\nok 1 - second\n1..1\n"
+    );
+}
+
+/// counts the flushes, to check the results are streamed
+struct Flushes<'a> {
+    output: &'a mut Vec<u8>,
+    lines: Vec<usize>,
+}
+
+impl io::Write for Flushes<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.output.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.lines
+            .push(self.output.iter().filter(|&&b| b == b'\n').count());
+        Ok(())
+    }
+}
+
+#[test]
+fn results_are_flushed_as_they_complete() {
+    let mut output = Vec::new();
+    let mut flushes = Flushes {
+        output: &mut output,
+        lines: Vec::new(),
+    };
+    tests().run_with(&mut Tap::new_with(&mut flushes));
+
+    // every result is flushed, not only the document once completed
+    let lines = flushes.lines;
+    assert_eq!(lines.len(), 10, "{:?}", lines);
+    assert_eq!(lines.first(), Some(&2), "{:?}", lines);
+}